@@ -0,0 +1,225 @@
+//! Machine-readable output: one JSON object per line, on the standard output.
+
+use crate::to_u64;
+use hyraigne::{
+    Event,
+    EventHandler,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::{
+    io::{
+        self,
+        Write,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// Report the progress of a download as JSON lines.
+pub(crate) struct Report {
+    /// What was done so far.
+    stats: Arc<Mutex<Stats>>,
+    /// When the download started.
+    start: Instant,
+}
+
+/// Statistics of a download.
+#[derive(Default)]
+struct Stats {
+    /// Number of chapters downloaded.
+    chapters: u64,
+    /// Number of pages downloaded.
+    pages: u64,
+    /// Number of pages skipped (already downloaded).
+    skipped: u64,
+    /// Number of bytes downloaded.
+    bytes: u64,
+    /// Number of errors.
+    errors: u64,
+}
+
+impl Report {
+    /// Start reporting a download.
+    pub(crate) fn new() -> Self {
+        Self {
+            stats: Arc::new(Mutex::new(Stats::default())),
+            start: Instant::now(),
+        }
+    }
+
+    /// Return the event handler reporting the events of the web spider.
+    pub(crate) fn handler(&self) -> EventHandler {
+        let stats = Arc::clone(&self.stats);
+
+        Arc::new(move |event: &Event| {
+            let value = match *event {
+                Event::SeriesResolved { ref title, ref url } => {
+                    json!({
+                        "event": "series_resolved",
+                        "title": title,
+                        "url": url.as_str(),
+                    })
+                },
+                Event::ChapterListed { id, ref url } => {
+                    json!({
+                        "event": "chapter_selected",
+                        "chapter": id,
+                        "url": url.as_str(),
+                    })
+                },
+                Event::ChapterStarted { id, pages } => {
+                    json!({
+                        "event": "chapter_started",
+                        "chapter": id,
+                        "pages": pages,
+                    })
+                },
+                Event::PageDownloaded {
+                    chapter,
+                    page,
+                    ref path,
+                    bytes,
+                    duration,
+                } => {
+                    update(&stats, |stats| {
+                        stats.pages += 1;
+                        stats.bytes += to_u64(bytes);
+                    });
+                    json!({
+                        "event": "page_saved",
+                        "chapter": chapter,
+                        "page": page,
+                        "path": path.to_string_lossy(),
+                        "bytes": bytes,
+                        "duration_ms": millis(duration),
+                    })
+                },
+                Event::PageSkipped {
+                    chapter,
+                    page,
+                    ref path,
+                } => {
+                    update(&stats, |stats| stats.skipped += 1);
+                    json!({
+                        "event": "page_skipped",
+                        "chapter": chapter,
+                        "page": page,
+                        "path": path.to_string_lossy(),
+                    })
+                },
+                Event::ChapterFinished { id } => {
+                    update(&stats, |stats| stats.chapters += 1);
+                    json!({
+                        "event": "chapter_finished",
+                        "chapter": id,
+                    })
+                },
+                Event::Error {
+                    chapter,
+                    kind,
+                    ref url,
+                    ref message,
+                } => {
+                    update(&stats, |stats| stats.errors += 1);
+                    json!({
+                        "event": "error",
+                        "chapter": chapter,
+                        "kind": kind,
+                        "url": url,
+                        "message": message,
+                    })
+                },
+                // Unknown events are not worth breaking the output.
+                _ => return,
+            };
+            emit(&value);
+        })
+    }
+
+    /// Report an error that wasn't signaled by the web spider.
+    pub(crate) fn error(&self, err: &hyraigne::Error) {
+        update(&self.stats, |stats| stats.errors += 1);
+
+        let mut value = error(err);
+        value["event"] = "error".into();
+        emit(&value);
+    }
+
+    /// Report the outcome of the download.
+    pub(crate) fn summary(&self, res: &hyraigne::Result<()>) {
+        let mut value = json!({
+            "event": "summary",
+            "status": match *res {
+                Ok(()) => "ok",
+                Err(hyraigne::Error::Cancelled) => "cancelled",
+                Err(_) => "failed",
+            },
+            "duration_ms": millis(self.start.elapsed()),
+        });
+        if let Ok(stats) = self.stats.lock() {
+            value["chapters"] = stats.chapters.into();
+            value["pages"] = stats.pages.into();
+            value["skipped"] = stats.skipped.into();
+            value["bytes"] = stats.bytes.into();
+            value["errors"] = stats.errors.into();
+        }
+        if let Err(ref err) = *res {
+            value["error"] = error(err);
+        }
+
+        emit(&value);
+    }
+}
+
+/// Print a series found while searching or browsing.
+pub(crate) fn print_series(
+    out: &mut impl Write,
+    summary: &hyraigne::SeriesSummary,
+) -> io::Result<()> {
+    let value = json!({
+        "title": summary.title(),
+        "url": summary.url().as_str(),
+    });
+
+    writeln!(out, "{}", value)
+}
+
+/// Describe `err` as a JSON object.
+fn error(err: &hyraigne::Error) -> Value {
+    json!({
+        "kind": err.kind(),
+        "url": err.url(),
+        "message": err.to_string(),
+    })
+}
+
+/// Update the statistics, unless a reporting thread panicked.
+fn update(stats: &Mutex<Stats>, f: impl FnOnce(&mut Stats)) {
+    if let Ok(mut stats) = stats.lock() {
+        f(&mut stats);
+    }
+}
+
+/// Print `value` on its own line.
+fn emit(value: &Value) {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    if let Err(err) = writeln!(stdout, "{}", value) {
+        log::error!("cannot write to the standard output: {}", err);
+    }
+}
+
+/// Convert `duration` into milliseconds.
+fn millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + u64::from(duration.subsec_millis())
+}
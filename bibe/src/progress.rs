@@ -0,0 +1,99 @@
+//! Progress bars, driven by the events of the web spiders.
+
+use crate::to_u64;
+use hyraigne::{
+    Event,
+    EventHandler,
+};
+use indicatif::{
+    MultiProgress,
+    ProgressBar,
+    ProgressStyle,
+};
+use std::sync::Arc;
+
+/// Progress of a download: chapters overall, pages of the current chapter and
+/// downloaded bytes.
+pub(crate) struct Progress {
+    /// Chapters downloaded so far.
+    chapters: ProgressBar,
+    /// Pages of the current chapter downloaded so far.
+    pages: ProgressBar,
+    /// Bytes downloaded so far.
+    bytes: ProgressBar,
+}
+
+impl Progress {
+    /// Draw the progress bars on the standard error, if it's a terminal.
+    pub(crate) fn new() -> Self {
+        let multi = MultiProgress::new();
+        let chapters = multi.add(ProgressBar::new(0).with_style(style(
+            "{prefix:.bold} [{bar:40}] {pos}/{len} chapters (ETA {eta})",
+        )));
+        let pages =
+            multi.add(ProgressBar::new(0).with_style(style(
+                "{prefix:.bold} [{bar:40}] {pos}/{len} pages",
+            )));
+        let bytes = multi.add(
+            ProgressBar::new(0)
+                .with_style(style("{bytes} downloaded ({bytes_per_sec})")),
+        );
+
+        Self {
+            chapters,
+            pages,
+            bytes,
+        }
+    }
+
+    /// Return the event handler updating the progress bars.
+    pub(crate) fn handler(&self) -> EventHandler {
+        let chapters = self.chapters.clone();
+        let pages = self.pages.clone();
+        let bytes = self.bytes.clone();
+
+        Arc::new(move |event: &Event| {
+            match *event {
+                Event::SeriesResolved { ref title, .. } => {
+                    chapters.set_prefix(title.clone());
+                },
+                Event::ChapterListed { .. } => chapters.inc_length(1),
+                Event::ChapterStarted { id, pages: count } => {
+                    pages.reset();
+                    pages.set_length(to_u64(count));
+                    pages.set_prefix(format!("chapter {}", id));
+                },
+                Event::PageDownloaded { bytes: size, .. } => {
+                    pages.inc(1);
+                    bytes.inc(to_u64(size));
+                },
+                Event::PageSkipped { .. } => pages.inc(1),
+                Event::ChapterFinished { .. } => chapters.inc(1),
+                // Failed chapters are reported by `Progress::skip_chapter`.
+                _ => (),
+            }
+        })
+    }
+
+    /// Count a chapter that couldn't be downloaded as done.
+    pub(crate) fn skip_chapter(&self) {
+        self.chapters.inc(1);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.chapters.abandon();
+        self.pages.finish_and_clear();
+        self.bytes.abandon();
+    }
+}
+
+/// Build a progress bar style from `template`.
+#[allow(clippy::expect_used)] // Hardcoded templates should be valid…
+fn style(template: &str) -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template(template)
+        .expect("valid progress bar template")
+        .progress_chars("=> ")
+}
@@ -1,17 +1,68 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
 use thiserror::Error;
 
 /// A specialized Result type for scraping operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Underlying cause of an error.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Number of bytes of the response body kept in HTTP errors.
+const MAX_BODY_SNIPPET: usize = 256;
+
 /// An error that may occur while scraping a website.
 #[derive(Debug, Error)]
 pub enum Error {
-    /// Error while querying the website.
+    /// Error while querying the website (connection failure, …).
     #[error("network request failed for {url}")]
     Network {
         /// Requested URL.
         url: String,
+        /// Underlying error.
+        source: BoxError,
+    },
+
+    /// The website answered with an error status.
+    #[error("HTTP request failed for {url}: status code {status}")]
+    Http {
+        /// Requested URL.
+        url: String,
+        /// HTTP status code.
+        status: u16,
+        /// Beginning of the response body.
+        body: String,
+    },
+
+    /// The requested resource doesn't exist (anymore).
+    #[error("{url} not found")]
+    NotFound {
+        /// Requested URL.
+        url: String,
+        /// HTTP status code (404 or 410).
+        status: u16,
+        /// Beginning of the response body.
+        body: String,
+    },
+
+    /// Too many requests were sent to the website.
+    #[error("rate limited by the website for {url}")]
+    RateLimited {
+        /// Requested URL.
+        url: String,
+        /// How long to wait before retrying, if specified by the website.
+        retry_after: Option<Duration>,
+    },
+
+    /// The request was blocked by an anti-bot challenge (Cloudflare, …).
+    #[error("request to {url} blocked by {protection}")]
+    Blocked {
+        /// Requested URL.
+        url: String,
+        /// Protection that blocked the request.
+        protection: &'static str,
     },
 
     /// Error while decoding the received payload.
@@ -19,12 +70,33 @@ pub enum Error {
     Payload {
         /// Origin URL of the payload.
         url: String,
+        /// Underlying error.
+        source: BoxError,
     },
 
     /// Error while scraping payload (HTML, JSON, …).
     #[error("scraping failed: {0}")]
     Scraping(String),
 
+    /// Invalid configuration (site definition, …).
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
+    /// The requested operation is not supported by the website.
+    #[error("{0} is not supported by this website")]
+    Unsupported(&'static str),
+
+    /// The URL is not supported (unknown website, unexpected page, …).
+    #[error("unsupported URL {url}")]
+    UnsupportedUrl {
+        /// The URL.
+        url: String,
+    },
+
+    /// The operation was cancelled (see `CancellationToken`).
+    #[error("operation cancelled")]
+    Cancelled,
+
     /// Error while interacting with the filesystem.
     #[error("I/O operation failed: {operation} {target}")]
     Filesystem {
@@ -36,3 +108,130 @@ pub enum Error {
         source: std::io::Error,
     },
 }
+
+impl Error {
+    /// Build the error matching the HTTP error `status` received for `url`.
+    ///
+    /// Only the beginning of `body` is kept, lossily decoded.
+    pub(crate) fn from_status(
+        url: &str,
+        status: u16,
+        retry_after: Option<Duration>,
+        body: &[u8],
+    ) -> Self {
+        let url = url.to_owned();
+        let body =
+            String::from_utf8_lossy(&body[..body.len().min(MAX_BODY_SNIPPET)])
+                .into_owned();
+
+        match status {
+            404 | 410 => Self::NotFound { url, status, body },
+            429 => Self::RateLimited { url, retry_after },
+            _ => Self::Http { url, status, body },
+        }
+    }
+
+    /// HTTP status code of the response that caused the error, if any.
+    #[must_use]
+    pub const fn status(&self) -> Option<u16> {
+        match *self {
+            Self::Http { status, .. } | Self::NotFound { status, .. } => {
+                Some(status)
+            },
+            Self::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+
+    /// Short, stable, name of the kind of error (e.g. `not_found`).
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match *self {
+            Self::Network { .. } => "network",
+            Self::Http { .. } => "http",
+            Self::NotFound { .. } => "not_found",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Blocked { .. } => "blocked",
+            Self::Payload { .. } => "payload",
+            Self::Scraping(_) => "scraping",
+            Self::Config(_) => "config",
+            Self::Unsupported(_) => "unsupported",
+            Self::UnsupportedUrl { .. } => "unsupported_url",
+            Self::Cancelled => "cancelled",
+            Self::Filesystem { .. } => "filesystem",
+        }
+    }
+
+    /// URL involved in the error, if any.
+    #[must_use]
+    pub fn url(&self) -> Option<&str> {
+        match *self {
+            Self::Network { ref url, .. }
+            | Self::Http { ref url, .. }
+            | Self::NotFound { ref url, .. }
+            | Self::RateLimited { ref url, .. }
+            | Self::Blocked { ref url, .. }
+            | Self::Payload { ref url, .. }
+            | Self::UnsupportedUrl { ref url } => Some(url),
+            _ => None,
+        }
+    }
+
+    /// Test if the operation may succeed if retried later.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        match *self {
+            Self::Network { .. } | Self::RateLimited { .. } => true,
+            Self::Http { status, .. } => status >= 500,
+            _ => false,
+        }
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        let url = "https://example.com/";
+
+        let from_status = |status| Error::from_status(url, status, None, b"");
+
+        assert!(matches!(from_status(410), Error::NotFound { .. }));
+        assert!(from_status(429).is_retryable());
+        assert!(from_status(503).is_retryable());
+        assert!(!from_status(403).is_retryable());
+        assert_eq!(from_status(403).status(), Some(403));
+        assert_eq!(from_status(410).status(), Some(410));
+        assert_eq!(from_status(404).kind(), "not_found");
+        assert_eq!(from_status(404).url(), Some(url));
+    }
+
+    #[test]
+    fn test_body_snippet() {
+        let url = "https://example.com/";
+        let body = "Oops".repeat(100);
+
+        let err = Error::from_status(url, 500, None, body.as_bytes());
+
+        assert!(matches!(
+            err,
+            Error::Http { ref body, .. } if body.len() == MAX_BODY_SNIPPET
+        ));
+    }
+
+    #[test]
+    fn test_source() {
+        let err = Error::Payload {
+            url: "https://example.com/".to_owned(),
+            source: Box::new(serde_json::from_str::<u8>("nope").err().unwrap()),
+        };
+
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
+
+// }}}
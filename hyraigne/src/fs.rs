@@ -8,6 +8,7 @@ use once_cell::unsync::Lazy;
 use regex::Regex;
 use std::{
     fs,
+    io,
     path::{
         Path,
         PathBuf,
@@ -54,10 +55,11 @@ pub(crate) fn extname_from_url(url: &Url) -> &str {
 
 /// Write a file atomically.
 pub(crate) fn atomic_save(path: &Path, data: &[u8]) -> Result<()> {
-    let mut tmp_path = path.to_path_buf();
-    tmp_path.set_extension("tmp");
+    let tmp_path = tmp_path(path);
 
     fs::write(&tmp_path, data).map_err(|err| {
+        // Don't leave a partial file behind.
+        fs::remove_file(&tmp_path).ok();
         Error::Filesystem {
             operation: "write",
             target: tmp_path.clone(),
@@ -74,6 +76,49 @@ pub(crate) fn atomic_save(path: &Path, data: &[u8]) -> Result<()> {
     })
 }
 
+/// Path of the temporary file used by `atomic_save` to write `path`.
+///
+/// The suffix is appended rather than replacing the extension, so that files
+/// only differing by their extension don't share their temporary file.
+pub(crate) fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+
+    tmp_path.into()
+}
+
+/// Remove the temporary files left in `dir` by an interrupted `atomic_save`.
+pub(crate) fn remove_tmp_files(dir: &Path) -> Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // Nothing to clean up.
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(Error::Filesystem {
+                operation: "list",
+                target: dir.to_path_buf(),
+                source: err,
+            })
+        },
+    };
+
+    for entry in entries.filter_map(std::result::Result::ok) {
+        let path = entry.path();
+        if path.extension().map_or(false, |ext| ext == "tmp") {
+            log::debug!("removing {}", path.display());
+            fs::remove_file(&path).map_err(|err| {
+                Error::Filesystem {
+                    operation: "remove",
+                    target: path.clone(),
+                    source: err,
+                }
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
 // Tests {{{
 
 #[cfg(test)]
@@ -104,6 +149,18 @@ mod tests {
         assert_eq!(sanitize_name("foo\\bar"), expected);
         assert_eq!(sanitize_name("foo\"bar"), expected);
     }
+
+    #[test]
+    fn test_tmp_path() {
+        assert_eq!(
+            tmp_path(Path::new("cache/entry.body")),
+            PathBuf::from("cache/entry.body.tmp")
+        );
+        assert_eq!(
+            tmp_path(Path::new("cache/entry")),
+            PathBuf::from("cache/entry.tmp")
+        );
+    }
 }
 
 // }}}
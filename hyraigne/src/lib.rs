@@ -56,25 +56,57 @@ mod error;
 mod fs;
 mod sites;
 mod spiders;
+#[cfg(test)]
+mod testing;
 mod types;
 mod utils;
 
 pub use error::{
+    BoxError,
     Error,
     Result,
 };
 
 // Can't merge the two "pub use" here.
 // See https://github.com/rust-lang/rust/issues/64762
+pub use sites::get_all_spiders;
+pub use sites::get_spider_by_name;
 pub use sites::get_spider_for;
+#[cfg(feature = "async")]
+pub use sites::AsyncSite;
+pub use sites::MadaraQuirks;
+pub use sites::SelectorOverrides;
 pub use sites::Site;
+pub use sites::SiteDefinition;
+pub use sites::SiteFactory;
+pub use sites::SiteRegistry;
+pub use sites::UrlMatcher;
 
 // Ditto, same bug…
+#[cfg(feature = "async")]
+pub use spiders::AsyncTransport;
+pub use spiders::Cassette;
+pub use spiders::CookieJar;
+pub use spiders::HttpCache;
+pub use spiders::MemoryTransport;
+pub use spiders::Request;
+pub use spiders::Response;
+pub use spiders::Transport;
+
+// Ditto, same bug…
+pub use types::CancellationToken;
 pub use types::Chapter;
+pub use types::Event;
+pub use types::EventHandler;
 pub use types::Filter;
+pub use types::LinkKind;
+pub use types::Listing;
 pub use types::Options;
 pub use types::Page;
+pub use types::Proxy;
+pub use types::SelectorCheck;
 pub use types::Series;
+pub use types::SeriesSummary;
 
 // To test examples in the README.
 // From https://github.com/rust-lang/cargo/issues/383#issuecomment-720873790
@@ -0,0 +1,313 @@
+//! The async engine, running a scraper on top of the async HTTP client.
+
+use super::{
+    download::{
+        Download,
+        ImageServers,
+    },
+    scraper::{
+        self,
+        ChapterLink,
+        Document,
+        Scraper,
+        MAX_CHAPTER_LIST_PAGES,
+    },
+    AsyncSite,
+};
+use crate::{
+    spiders::AsyncHttpClient,
+    types::Events,
+    Chapter,
+    Error,
+    Filter,
+    LinkKind,
+    Listing,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+    SeriesSummary,
+};
+use std::{
+    path::PathBuf,
+    slice,
+    time::Instant,
+};
+use url::Url;
+
+/// An async web spider, scraping a website with `S`.
+pub(crate) struct AsyncEngine<S> {
+    /// Scraping logic of the website.
+    scraper: S,
+    /// HTTP client.
+    spider: AsyncHttpClient,
+    /// Image servers, to download the pages from.
+    servers: ImageServers,
+    /// Output directory.
+    pub(super) output: PathBuf,
+    /// Where the progress events are sent.
+    events: Events,
+}
+
+impl<S: Scraper> AsyncEngine<S> {
+    /// Initialize the web spider with the given options.
+    pub(crate) fn new(scraper: S, options: Options) -> Self {
+        let cookies = scraper.cookies(&options);
+
+        Self {
+            spider: AsyncHttpClient::new(&options, cookies),
+            servers: ImageServers::default(),
+            events: options.events.clone(),
+            output: options.output,
+            scraper,
+        }
+    }
+
+    /// Retrieve the document at `url`.
+    async fn fetch(&self, url: &Url) -> Result<Vec<u8>> {
+        self.spider.get(url, self.scraper.format().accept()).await
+    }
+
+    /// Parse the `body` served at `url`, and extract `what` with `scrape`.
+    ///
+    /// HTML documents can't be sent across threads: they are never kept
+    /// across an await point.
+    fn scrape<T, F>(
+        &self,
+        what: &str,
+        url: &Url,
+        body: Vec<u8>,
+        scrape: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(&S, &Document) -> Result<T>,
+    {
+        let document = Document::parse(self.scraper.format(), url, body)?;
+
+        scrape(&self.scraper, &document)
+            .map_err(|err| scraper::scraping_error(what, url, &err))
+    }
+
+    /// Resolve the chapter (or page) link `url`.
+    async fn resolve_chapter(&self, url: &Url) -> Result<ChapterLink> {
+        if let Some(link) = self.scraper.parse_chapter_link(url) {
+            return Ok(link);
+        }
+
+        let url = self.scraper.chapter_url(url)?;
+
+        log::info!("scraping chapter info from {}…", url.as_str());
+
+        let body = self.fetch(&url).await?;
+        self.scrape("chapter", &url, body, |s, doc| s.scrape_chapter(&url, doc))
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Scraper> AsyncSite for AsyncEngine<S> {
+    async fn search(&self, query: &str) -> Result<Vec<SeriesSummary>> {
+        let url = self.scraper.search_url(query)?;
+
+        log::info!("searching series matching `{}`…", query);
+
+        let body = self.fetch(&url).await?;
+        let results = self.scrape("search results", &url, body, |s, doc| {
+            s.scrape_search(doc)
+        })?;
+        log::debug!("found {} series", results.len());
+
+        Ok(results)
+    }
+
+    async fn browse(
+        &self,
+        listing: Listing,
+        page: u16,
+    ) -> Result<Vec<SeriesSummary>> {
+        let url = match self.scraper.listing_url(listing, page)? {
+            Some(url) => url,
+            None => return Ok(Vec::new()),
+        };
+
+        log::info!("scraping series listing from {}…", url.as_str());
+
+        let body = self.fetch(&url).await?;
+        let results = self.scrape("series listing", &url, body, |s, doc| {
+            s.scrape_listing(listing, doc)
+        })?;
+        log::debug!("found {} series", results.len());
+
+        Ok(results)
+    }
+
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        self.scraper.check_selectors(html)
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        self.scraper.classify(&self.scraper.canonical_url(url))
+    }
+
+    async fn get_series(&self, url: &Url) -> Result<Series> {
+        let url = &self.scraper.canonical_url(url);
+        let url = match self.scraper.classify(url) {
+            LinkKind::Series => self.scraper.series_url(url)?,
+            LinkKind::Chapter | LinkKind::Page => {
+                let link = self.resolve_chapter(url).await?;
+                self.scraper.series_url(&link.series)?
+            },
+        };
+
+        log::info!("scraping series info from {}…", url.as_str());
+
+        let body = self.fetch(&url).await?;
+        let series = self
+            .scrape("serie", &url, body, |s, doc| s.scrape_series(&url, doc))?;
+
+        self.events.series_resolved(&series);
+
+        Ok(series)
+    }
+
+    async fn get_chapters<'a>(
+        &self,
+        series: &'a Series,
+        filter: Filter,
+    ) -> Result<Vec<Chapter<'a>>> {
+        log::info!("scraping chapter links for series {}…", series.title);
+
+        let mut chapters = Vec::new();
+        for index in 0..MAX_CHAPTER_LIST_PAGES {
+            let url = match self.scraper.chapters_url(series, &filter, index) {
+                Some(url) => url,
+                None => break,
+            };
+
+            log::info!("extracting chapters from {}…", url.as_str());
+
+            let body = self.fetch(&url).await?;
+            let (mut page, request) =
+                self.scrape("chapters", &url, body, |s, doc| {
+                    let page = s.scrape_chapters(&url, series, &filter, doc)?;
+                    let request = if page.is_empty() {
+                        s.chapters_request(&url, doc)?
+                    } else {
+                        None
+                    };
+                    Ok((page, request))
+                })?;
+            if let Some(request) = request {
+                let url = request.url().clone();
+
+                log::info!("loading chapters from {}…", url.as_str());
+
+                let format = self.scraper.format();
+                let body = self.spider.send(request, format.accept()).await?;
+                page = self.scrape("chapters", &url, body, |s, doc| {
+                    s.scrape_chapters(&url, series, &filter, doc)
+                })?;
+            }
+            if !scraper::merge_chapters(&mut chapters, page) {
+                break;
+            }
+        }
+
+        Ok(scraper::select_chapters(
+            &self.scraper,
+            chapters,
+            &filter,
+            &self.events,
+        ))
+    }
+
+    async fn get_chapter<'a>(
+        &self,
+        series: &'a Series,
+        url: &Url,
+    ) -> Result<Chapter<'a>> {
+        let url = &self.scraper.canonical_url(url);
+        if self.scraper.classify(url) == LinkKind::Series {
+            return Err(Error::UnsupportedUrl {
+                url: url.to_string(),
+            });
+        }
+
+        let chapter = self.resolve_chapter(url).await?.into_chapter(series);
+        log::debug!("found chapter {} at {}", chapter.id, chapter.url.as_str());
+
+        self.events.chapters_listed(slice::from_ref(&chapter));
+
+        Ok(chapter)
+    }
+
+    async fn get_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+    ) -> Result<Vec<Page<'a>>> {
+        log::info!("scraping page links for chapter {}…", chapter.id);
+
+        let body = self.fetch(&chapter.url).await?;
+        let pages = self.scrape("pages", &chapter.url, body, |s, doc| {
+            s.scrape_pages(chapter, doc)
+        })?;
+
+        log::debug!("found {} pages in chapter {}", pages.len(), chapter.id);
+
+        Ok(pages)
+    }
+
+    fn mkdir(&self, chapters: &[Chapter<'_>]) -> Result<()> {
+        for chapter in chapters {
+            let path = chapter.path(&self.output);
+            crate::fs::mkdir_p(&path)?;
+        }
+
+        Ok(())
+    }
+
+    async fn download(&self, pages: &[Page<'_>]) -> Result<()> {
+        let download = match Download::start(pages, &self.output, &self.events)
+        {
+            Some(download) => download,
+            None => return Ok(()),
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+
+        for page in pages {
+            let path = match download.target(page) {
+                Some(path) => path,
+                None => continue,
+            };
+
+            let start = Instant::now();
+            let referer = self.scraper.referer(page);
+            // Try each server in turn, until one works.
+            let mut res = Ok(());
+            for url in self.servers.urls(page) {
+                res = self.spider.get_image(url, referer, &mut bytes).await;
+                match res {
+                    Ok(()) => {
+                        self.servers.healthy(url);
+                        break;
+                    },
+                    Err(Error::Cancelled) => break,
+                    Err(ref err) => {
+                        log::warn!(
+                            "failed to download {}: {}",
+                            url.as_str(),
+                            err
+                        );
+                    },
+                }
+            }
+            download.save(page, path, res, &bytes, start)?;
+
+            bytes.clear();
+        }
+
+        download.finish();
+
+        Ok(())
+    }
+}
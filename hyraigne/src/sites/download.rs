@@ -0,0 +1,276 @@
+//! Download of the pages, shared by every web spider.
+
+use crate::{
+    types::Events,
+    Chapter,
+    Error,
+    Event,
+    Page,
+    Result,
+};
+use std::{
+    iter,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+    time::Instant,
+};
+use url::Url;
+
+/// Download `pages` into `output`, using `fetch` to retrieve each image.
+///
+/// Pages already downloaded are skipped.
+pub(crate) fn download_pages<F>(
+    pages: &[Page<'_>],
+    output: &Path,
+    events: &Events,
+    mut fetch: F,
+) -> Result<()>
+where
+    F: FnMut(&Page<'_>, &mut Vec<u8>) -> Result<()>,
+{
+    let download = match Download::start(pages, output, events) {
+        Some(download) => download,
+        None => return Ok(()),
+    };
+    let mut bytes: Vec<u8> = Vec::new();
+
+    for page in pages {
+        let path = match download.target(page) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let start = Instant::now();
+        let res = fetch(page, &mut bytes);
+        download.save(page, path, res, &bytes, start)?;
+
+        bytes.clear();
+    }
+
+    download.finish();
+
+    Ok(())
+}
+
+/// Bookkeeping of the download of a chapter, whatever the HTTP client.
+pub(crate) struct Download<'a> {
+    /// Chapter being downloaded.
+    chapter: &'a Chapter<'a>,
+    /// Output directory.
+    output: &'a Path,
+    /// Where the progress events are sent.
+    events: &'a Events,
+}
+
+impl<'a> Download<'a> {
+    /// Start the download of `pages`, if any.
+    pub(crate) fn start(
+        pages: &'a [Page<'a>],
+        output: &'a Path,
+        events: &'a Events,
+    ) -> Option<Self> {
+        let chapter = pages.first()?.chapter;
+
+        log::info!(
+            "downloading {} pages for chapter {}…",
+            pages.len(),
+            chapter.id,
+        );
+        events.emit(&Event::ChapterStarted {
+            id: chapter.id,
+            pages: pages.len(),
+        });
+
+        Some(Self {
+            chapter,
+            output,
+            events,
+        })
+    }
+
+    /// Return where `page` must be saved, unless it was already downloaded.
+    pub(crate) fn target(&self, page: &Page<'_>) -> Option<PathBuf> {
+        // Compute the image path.
+        let path = page.path(self.output);
+
+        // Skip it if it has already been downloaded.
+        if path.exists() {
+            log::debug!("{} already exists, skip", path.display());
+            self.events.emit(&Event::PageSkipped {
+                chapter: self.chapter.id,
+                page: page.id,
+                path,
+            });
+            return None;
+        }
+
+        log::info!("downloading {}…", path.display());
+
+        Some(path)
+    }
+
+    /// Save the `bytes` of `page` at `path`, if it was fetched successfully.
+    pub(crate) fn save(
+        &self,
+        page: &Page<'_>,
+        path: PathBuf,
+        fetched: Result<()>,
+        bytes: &[u8],
+        start: Instant,
+    ) -> Result<()> {
+        let res = fetched.and_then(|()| crate::fs::atomic_save(&path, bytes));
+        if let Err(Error::Cancelled) = res {
+            crate::fs::remove_tmp_files(&self.chapter.path(self.output))?;
+        }
+        res.map_err(|err| {
+            self.events.emit(&Event::Error {
+                chapter: self.chapter.id,
+                kind: err.kind(),
+                url: err.url().map(ToOwned::to_owned),
+                message: err.to_string(),
+            });
+            err
+        })?;
+        self.events.emit(&Event::PageDownloaded {
+            chapter: self.chapter.id,
+            page: page.id,
+            path,
+            bytes: bytes.len(),
+            duration: start.elapsed(),
+        });
+
+        Ok(())
+    }
+
+    /// Signal that every page was downloaded.
+    pub(crate) fn finish(self) {
+        self.events.emit(&Event::ChapterFinished {
+            id: self.chapter.id,
+        });
+    }
+}
+
+/// Image servers of a website, remembering the last one that worked.
+///
+/// Once a server fails, the pages are fetched from the first alternate server
+/// that works for the rest of the run, instead of trying the broken one again
+/// for every page.
+#[derive(Default)]
+pub(crate) struct ImageServers {
+    /// Host of the last server that served an image, if any.
+    healthy: Mutex<Option<String>>,
+}
+
+impl ImageServers {
+    /// Return the URLs of `page`, in the order they should be tried.
+    ///
+    /// That's the main URL then the fallbacks, except that the URL on the
+    /// healthy server (if known) comes first.
+    pub(crate) fn urls<'a>(&self, page: &'a Page<'_>) -> Vec<&'a Url> {
+        let mut urls = iter::once(&page.main)
+            .chain(&page.fallbacks)
+            .collect::<Vec<_>>();
+
+        if let Some(ref host) = *self.lock() {
+            let healthy = urls
+                .iter()
+                .position(|url| url.host_str() == Some(host.as_str()));
+            if let Some(index) = healthy {
+                urls[..=index].rotate_right(1);
+            }
+        }
+
+        urls
+    }
+
+    /// Remember that the server of `url` is healthy.
+    pub(crate) fn healthy(&self, url: &Url) {
+        let host = url.host_str().map(ToOwned::to_owned);
+        let mut healthy = self.lock();
+
+        if *healthy != host {
+            if healthy.is_some() {
+                log::info!(
+                    "switching to image server {}",
+                    host.as_deref().unwrap_or_default()
+                );
+            }
+            *healthy = host;
+        }
+    }
+
+    /// Lock the state, even if another thread panicked while holding it.
+    fn lock(&self) -> MutexGuard<'_, Option<String>> {
+        self.healthy.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::Pagination,
+        Series,
+    };
+
+    #[test]
+    fn test_image_servers() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let chapter = Chapter {
+            id: 1.0,
+            series: &series,
+            volume: None,
+            url: Url::parse("http://example.com/1/").unwrap(),
+        };
+        let page = Page {
+            id: 1,
+            chapter: &chapter,
+            main: Url::parse("http://s1.example.com/1.jpg").unwrap(),
+            fallbacks: vec![
+                Url::parse("http://s2.example.com/1.jpg").unwrap(),
+                Url::parse("http://s3.example.com/1.jpg").unwrap(),
+            ],
+        };
+        let servers = ImageServers::default();
+        let hosts = |servers: &ImageServers| {
+            servers
+                .urls(&page)
+                .iter()
+                .map(|url| url.host_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            hosts(&servers),
+            ["s1.example.com", "s2.example.com", "s3.example.com"]
+        );
+
+        servers.healthy(&page.fallbacks[1]);
+        assert_eq!(
+            hosts(&servers),
+            ["s3.example.com", "s1.example.com", "s2.example.com"]
+        );
+
+        // Unknown to this page: keep the default order.
+        servers.healthy(&Url::parse("http://s4.example.com/").unwrap());
+        assert_eq!(
+            hosts(&servers),
+            ["s1.example.com", "s2.example.com", "s3.example.com"]
+        );
+    }
+}
+
+// }}}
@@ -0,0 +1,303 @@
+//! The blocking engine, running a scraper on top of the blocking HTTP client.
+
+use super::{
+    download::ImageServers,
+    scraper::{
+        self,
+        ChapterLink,
+        Document,
+        Scraper,
+        MAX_CHAPTER_LIST_PAGES,
+    },
+    Site,
+};
+use crate::{
+    spiders::HttpClient,
+    types::Events,
+    Chapter,
+    Error,
+    Filter,
+    LinkKind,
+    Listing,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+    SeriesSummary,
+};
+use std::{
+    path::PathBuf,
+    slice,
+};
+use url::Url;
+
+/// A blocking web spider, scraping a website with `S`.
+pub(crate) struct Engine<S> {
+    /// Scraping logic of the website.
+    scraper: S,
+    /// HTTP client.
+    spider: HttpClient,
+    /// Image servers, to download the pages from.
+    servers: ImageServers,
+    /// Output directory.
+    pub(super) output: PathBuf,
+    /// Where the progress events are sent.
+    events: Events,
+}
+
+impl<S: Scraper> Engine<S> {
+    /// Initialize the web spider with the given options.
+    pub(crate) fn new(scraper: S, options: Options) -> Self {
+        let cookies = scraper.cookies(&options);
+
+        Self {
+            spider: HttpClient::new(&options, cookies),
+            servers: ImageServers::default(),
+            events: options.events.clone(),
+            output: options.output,
+            scraper,
+        }
+    }
+
+    /// Retrieve and parse the document at `url`.
+    fn fetch(&self, url: &Url) -> Result<Document> {
+        let format = self.scraper.format();
+        let body = self.spider.get(url, format.accept())?;
+
+        Document::parse(format, url, body)
+    }
+
+    /// Load the chapters missing from the page at `url`, if the scraper knows
+    /// how to.
+    fn load_chapters<'a>(
+        &self,
+        url: &Url,
+        series: &'a Series,
+        filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        let request = match self
+            .scraper
+            .chapters_request(url, document)
+            .map_err(|err| scraper::scraping_error("chapters", url, &err))?
+        {
+            Some(request) => request,
+            None => return Ok(Vec::new()),
+        };
+        let url = request.url().clone();
+
+        log::info!("loading chapters from {}…", url.as_str());
+
+        let format = self.scraper.format();
+        let body = self.spider.send(request, format.accept())?;
+        let document = Document::parse(format, &url, body)?;
+        self.scraper
+            .scrape_chapters(&url, series, filter, &document)
+            .map_err(|err| scraper::scraping_error("chapters", &url, &err))
+    }
+
+    /// Resolve the chapter (or page) link `url`.
+    fn resolve_chapter(&self, url: &Url) -> Result<ChapterLink> {
+        if let Some(link) = self.scraper.parse_chapter_link(url) {
+            return Ok(link);
+        }
+
+        let url = self.scraper.chapter_url(url)?;
+
+        log::info!("scraping chapter info from {}…", url.as_str());
+
+        let document = self.fetch(&url)?;
+        self.scraper
+            .scrape_chapter(&url, &document)
+            .map_err(|err| scraper::scraping_error("chapter", &url, &err))
+    }
+}
+
+impl<S: Scraper> Site for Engine<S> {
+    fn search(&self, query: &str) -> Result<Vec<SeriesSummary>> {
+        let url = self.scraper.search_url(query)?;
+
+        log::info!("searching series matching `{}`…", query);
+
+        let document = self.fetch(&url)?;
+        let results = self.scraper.scrape_search(&document).map_err(|err| {
+            scraper::scraping_error("search results", &url, &err)
+        })?;
+        log::debug!("found {} series", results.len());
+
+        Ok(results)
+    }
+
+    fn browse(
+        &self,
+        listing: Listing,
+        page: u16,
+    ) -> Result<Vec<SeriesSummary>> {
+        let url = match self.scraper.listing_url(listing, page)? {
+            Some(url) => url,
+            None => return Ok(Vec::new()),
+        };
+
+        log::info!("scraping series listing from {}…", url.as_str());
+
+        let document = self.fetch(&url)?;
+        let results =
+            self.scraper
+                .scrape_listing(listing, &document)
+                .map_err(|err| {
+                    scraper::scraping_error("series listing", &url, &err)
+                })?;
+        log::debug!("found {} series", results.len());
+
+        Ok(results)
+    }
+
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        self.scraper.check_selectors(html)
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        self.scraper.classify(&self.scraper.canonical_url(url))
+    }
+
+    fn get_series(&self, url: &Url) -> Result<Series> {
+        let url = &self.scraper.canonical_url(url);
+        let url = match self.scraper.classify(url) {
+            LinkKind::Series => self.scraper.series_url(url)?,
+            LinkKind::Chapter | LinkKind::Page => {
+                let link = self.resolve_chapter(url)?;
+                self.scraper.series_url(&link.series)?
+            },
+        };
+
+        log::info!("scraping series info from {}…", url.as_str());
+
+        let document = self.fetch(&url)?;
+        let series = self
+            .scraper
+            .scrape_series(&url, &document)
+            .map_err(|err| scraper::scraping_error("serie", &url, &err))?;
+
+        self.events.series_resolved(&series);
+
+        Ok(series)
+    }
+
+    fn get_chapters<'a>(
+        &self,
+        series: &'a Series,
+        filter: Filter,
+    ) -> Result<Vec<Chapter<'a>>> {
+        log::info!("scraping chapter links for series {}…", series.title);
+
+        let mut chapters = Vec::new();
+        for index in 0..MAX_CHAPTER_LIST_PAGES {
+            let url = match self.scraper.chapters_url(series, &filter, index) {
+                Some(url) => url,
+                None => break,
+            };
+
+            log::info!("extracting chapters from {}…", url.as_str());
+
+            let document = self.fetch(&url)?;
+            let mut page = self
+                .scraper
+                .scrape_chapters(&url, series, &filter, &document)
+                .map_err(|err| {
+                    scraper::scraping_error("chapters", &url, &err)
+                })?;
+            if page.is_empty() {
+                page = self.load_chapters(&url, series, &filter, &document)?;
+            }
+            if !scraper::merge_chapters(&mut chapters, page) {
+                break;
+            }
+        }
+
+        Ok(scraper::select_chapters(
+            &self.scraper,
+            chapters,
+            &filter,
+            &self.events,
+        ))
+    }
+
+    fn get_chapter<'a>(
+        &self,
+        series: &'a Series,
+        url: &Url,
+    ) -> Result<Chapter<'a>> {
+        let url = &self.scraper.canonical_url(url);
+        if self.scraper.classify(url) == LinkKind::Series {
+            return Err(Error::UnsupportedUrl {
+                url: url.to_string(),
+            });
+        }
+
+        let chapter = self.resolve_chapter(url)?.into_chapter(series);
+        log::debug!("found chapter {} at {}", chapter.id, chapter.url.as_str());
+
+        self.events.chapters_listed(slice::from_ref(&chapter));
+
+        Ok(chapter)
+    }
+
+    fn get_pages<'a>(&self, chapter: &'a Chapter<'_>) -> Result<Vec<Page<'a>>> {
+        log::info!("scraping page links for chapter {}…", chapter.id);
+
+        let document = self.fetch(&chapter.url)?;
+        let pages =
+            self.scraper
+                .scrape_pages(chapter, &document)
+                .map_err(|err| {
+                    scraper::scraping_error("pages", &chapter.url, &err)
+                })?;
+
+        log::debug!("found {} pages in chapter {}", pages.len(), chapter.id);
+
+        Ok(pages)
+    }
+
+    fn mkdir(&self, chapters: &[Chapter<'_>]) -> Result<()> {
+        for chapter in chapters {
+            let path = chapter.path(&self.output);
+            crate::fs::mkdir_p(&path)?;
+        }
+
+        Ok(())
+    }
+
+    fn download(&self, pages: &[Page<'_>]) -> Result<()> {
+        super::download::download_pages(
+            pages,
+            &self.output,
+            &self.events,
+            |page, bytes| {
+                let referer = self.scraper.referer(page);
+
+                // Try each server in turn, until one works.
+                let mut res = Ok(());
+                for url in self.servers.urls(page) {
+                    res = self.spider.get_image(url, referer, bytes);
+                    match res {
+                        Ok(()) => {
+                            self.servers.healthy(url);
+                            break;
+                        },
+                        Err(Error::Cancelled) => break,
+                        Err(ref err) => {
+                            log::warn!(
+                                "failed to download {}: {}",
+                                url.as_str(),
+                                err
+                            );
+                        },
+                    }
+                }
+
+                res
+            },
+        )
+    }
+}
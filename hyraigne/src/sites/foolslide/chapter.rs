@@ -0,0 +1,209 @@
+use super::selectors::Selectors;
+use crate::{
+    sites::scraper::ChapterLink,
+    Chapter,
+    Error,
+    LinkKind,
+    Result,
+    Series,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Extract every chapter listed in the given HTML.
+///
+/// Chapters that can't be identified (see `parse_link`) are skipped, rather
+/// than preventing the download of the others.
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    series: &'a Series,
+    selectors: &Selectors,
+) -> Result<Vec<Chapter<'a>>> {
+    let mut chapters = Vec::new();
+
+    for link in selectors.chapter.filter(html.descendants().elements()) {
+        let url = url_from_element(&link)?;
+        if let Some((link, _)) = parse_link(&url) {
+            chapters.push(link.into_chapter(series));
+        } else {
+            log::warn!("skipping chapter {}: unsupported URL", url);
+        }
+    }
+
+    Ok(chapters)
+}
+
+/// Parse a link to the reader, and tell if it points to a chapter or a page.
+///
+/// Reader URLs look like `/read/<series>/<language>/<volume>/<chapter>/`,
+/// followed by the subchapter (if any) and the page (`page/<number>`), maybe
+/// below the path where `FoOlSlide` is installed. The volume is `0` when the
+/// chapter isn't part of a volume yet.
+///
+/// The subchapter becomes the thousandths of the chapter ID (e.g. `12/5` is
+/// 12.005 and `12/10` is 12.01), so subchapters above 999 are rejected.
+pub(super) fn parse_link(url: &Url) -> Option<(ChapterLink, LinkKind)> {
+    let segments = url
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let read = segments.iter().position(|&segment| segment == "read")?;
+    let (prefix, reader) = segments.split_at(read);
+
+    let (slug, volume, chapter, rest) = match *reader {
+        [_, slug, _, volume, chapter, ref rest @ ..] => {
+            (slug, volume, chapter, rest)
+        },
+        _ => return None,
+    };
+    let (subchapter, kind) = match *rest {
+        [] => (None, LinkKind::Chapter),
+        [subchapter] => (Some(subchapter), LinkKind::Chapter),
+        ["page", _] => (None, LinkKind::Page),
+        [subchapter, "page", _] => (Some(subchapter), LinkKind::Page),
+        _ => return None,
+    };
+
+    let chapter = chapter.parse::<u16>().ok()?;
+    let sub = subchapter.map_or(Some(0), |sub| sub.parse::<u16>().ok())?;
+    if sub > 999 {
+        log::debug!("subchapter {} of {} is out of range", sub, url);
+        return None;
+    }
+    // Divide last, to get the closest float (12005 / 1000 rather than
+    // 12 + 0.005).
+    let id = (f64::from(chapter) * 1000.0 + f64::from(sub)) / 1000.0;
+    let volume = match volume.parse::<u16>().ok()? {
+        0 => None,
+        volume => Some(volume.to_string()),
+    };
+
+    // Drop the page, if any.
+    let length = read + 5 + usize::from(subchapter.is_some());
+    let mut chapter_url = url.clone();
+    chapter_url.set_path(&format!("/{}/", segments.get(..length)?.join("/")));
+    chapter_url.set_query(None);
+    chapter_url.set_fragment(None);
+
+    let mut series = chapter_url.clone();
+    series.set_path(&format!(
+        "/{}/",
+        [prefix, &["series", slug]].concat().join("/")
+    ));
+
+    Some((
+        ChapterLink {
+            series,
+            id,
+            volume,
+            url: chapter_url,
+        },
+        kind,
+    ))
+}
+
+/// Extract the chapter URL from a chapter link.
+fn url_from_element(
+    element: &kuchiki::NodeDataRef<kuchiki::ElementData>,
+) -> Result<Url> {
+    let attributes = element.attributes.borrow();
+    let url = attributes
+        .get("href")
+        .ok_or_else(|| Error::Scraping("chapter URL not found".to_owned()))?;
+
+    Url::parse(url).map_err(|err| {
+        Error::Scraping(format!("invalid chapter URL `{}`: {}", url, err))
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Pagination;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let series = Series {
+            title: "Moonlit Gardens".to_owned(),
+            url: Url::parse(
+                "https://reader.example.com/slide/series/moonlit-gardens/",
+            )
+            .unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/foolslide/series.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let chapters =
+            scrape_from_html(&document, &series, &Selectors::default())
+                .unwrap();
+
+        let ids = chapters
+            .iter()
+            .map(|chapter| chapter.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [13.0, 12.0, 11.005, 11.0, 2.0, 1.0]);
+        assert_eq!(chapters[0].volume, None);
+        assert_eq!(chapters[1].volume.as_deref(), Some("2"));
+        assert_eq!(
+            chapters[2].url.as_str(),
+            "https://reader.example.com/slide/read/moonlit-gardens/en/2/11/5/"
+        );
+    }
+
+    #[test]
+    fn test_parse_link() {
+        let parse = |url| parse_link(&Url::parse(url).unwrap());
+
+        let (link, kind) =
+            parse("https://reader.example.com/read/gardens/en/0/7/page/3")
+                .unwrap();
+        assert_eq!(kind, LinkKind::Page);
+        assert_eq!(link.id, 7.0);
+        assert_eq!(link.volume, None);
+        assert_eq!(
+            link.url.as_str(),
+            "https://reader.example.com/read/gardens/en/0/7/"
+        );
+        assert_eq!(
+            link.series.as_str(),
+            "https://reader.example.com/series/gardens/"
+        );
+
+        let (link, kind) =
+            parse("https://example.com/slide/read/gardens/fr/3/7/5/").unwrap();
+        assert_eq!(kind, LinkKind::Chapter);
+        assert_eq!(link.id, 7.005);
+        assert_eq!(link.volume.as_deref(), Some("3"));
+        assert_eq!(
+            link.series.as_str(),
+            "https://example.com/slide/series/gardens/"
+        );
+
+        assert!(parse("https://example.com/series/gardens/").is_none());
+        assert!(parse("https://example.com/read/gardens/en/0/").is_none());
+        assert!(parse("https://example.com/read/gardens/en/0/x/").is_none());
+    }
+
+    #[test]
+    fn test_parse_subchapter() {
+        let id = |url| {
+            parse_link(&Url::parse(url).unwrap()).map(|(link, _)| link.id)
+        };
+
+        assert_eq!(id("https://example.com/read/x/en/0/12/0/"), Some(12.0));
+        assert_eq!(id("https://example.com/read/x/en/0/12/1/"), Some(12.001));
+        assert_eq!(id("https://example.com/read/x/en/0/12/05/"), Some(12.005));
+        assert_eq!(id("https://example.com/read/x/en/0/12/10/"), Some(12.01));
+        assert_eq!(id("https://example.com/read/x/en/0/12/999/"), Some(12.999));
+        assert_eq!(id("https://example.com/read/x/en/0/12/1000/"), None);
+        assert_eq!(id("https://example.com/read/x/en/0/12/x/"), None);
+    }
+}
+
+// }}}
@@ -0,0 +1,14 @@
+//! Web spider for the readers built on `FoOlSlide`, an open-source comic
+//! reader hosted by many scanlation teams.
+//!
+//! Every reader shares the same markup and URL layout, so the same web spider
+//! serves them all: they only need to be registered by hostname.
+
+mod chapter;
+mod page;
+mod selectors;
+mod series;
+mod site;
+
+pub(super) use selectors::DEFAULTS as DEFAULT_SELECTORS;
+pub(crate) use site::Site;
@@ -0,0 +1,129 @@
+use super::selectors::Selectors;
+use crate::{
+    Chapter,
+    Error,
+    Page,
+    Result,
+};
+use kuchiki::traits::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+
+/// Locate the page list in the scripts of the reader page:
+/// `var pages = [{…}, …];`.
+#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
+pub(super) static PAGES_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"var\s+pages\s*=\s*\["#).expect("invalid pages regexp")
+});
+
+/// A page, as described in the page list.
+#[derive(Deserialize)]
+struct RawPage {
+    /// URL of the image.
+    url: String,
+}
+
+/// Scrape page links from the chapter's page HTML.
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    chapter: &'a Chapter<'_>,
+    selectors: &Selectors,
+) -> Result<Vec<Page<'a>>> {
+    let pages = selectors
+        .page_script
+        .filter(html.descendants().elements())
+        .find_map(|script| pages_from_script(&script.text_contents()))
+        .ok_or_else(|| Error::Scraping("page list not found".to_owned()))??;
+
+    pages
+        .iter()
+        .enumerate()
+        .map(|(idx, page)| {
+            let url = chapter.url.join(&page.url).map_err(|err| {
+                Error::Scraping(format!(
+                    "invalid page URL `{}`: {}",
+                    page.url, err
+                ))
+            })?;
+
+            #[allow(clippy::cast_possible_truncation)] // Page number is small.
+            Ok(Page {
+                id: (idx + 1) as u16,
+                chapter,
+                main: url,
+                fallbacks: Vec::new(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Extract the page list from `script`.
+///
+/// `None` is returned if the page list isn't defined there.
+fn pages_from_script(script: &str) -> Option<Result<Vec<RawPage>>> {
+    // Start from the opening bracket: the JSON array ends before the
+    // semicolon, and the rest of the script is ignored.
+    let start = PAGES_REGEX.find(script)?.end() - 1;
+    let pages = serde_json::Deserializer::from_str(script.get(start..)?)
+        .into_iter::<Vec<RawPage>>()
+        .next()?;
+
+    Some(
+        pages.map_err(|err| {
+            Error::Scraping(format!("invalid page list: {}", err))
+        }),
+    )
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::Pagination,
+        Series,
+    };
+    use std::path::PathBuf;
+    use url::Url;
+
+    #[test]
+    fn test_scraping() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let chapter = Chapter {
+            id: 12.0,
+            series: &series,
+            volume: None,
+            url: Url::parse("http://example.com/12/").unwrap(),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/foolslide/reader.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let pages =
+            scrape_from_html(&document, &chapter, &Selectors::default())
+                .unwrap();
+
+        assert_eq!(pages.len(), 6);
+        assert_eq!(
+            pages[0].main.as_str(),
+            "https://reader.example.com/slide/content/comics/moonlit-gardens_5f1c2a/2-12-0_the-well/01.jpg"
+        );
+    }
+
+    #[test]
+    fn test_invalid_page_list() {
+        let script = r#"var pages = [{"filename": "01.jpg"}];"#;
+
+        assert!(pages_from_script(script).unwrap().is_err());
+        assert!(pages_from_script("var title = document.title;").is_none());
+    }
+}
+
+// }}}
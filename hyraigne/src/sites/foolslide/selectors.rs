@@ -0,0 +1,105 @@
+//! CSS selectors to scrape the `FoOlSlide` readers.
+
+use super::page::PAGES_REGEX;
+use crate::{
+    sites::SelectorOverrides,
+    SelectorCheck,
+};
+use kuchiki::traits::*;
+
+/// Name of the web spider, to look up the overrides.
+///
+/// Overrides apply to every `FoOlSlide` reader, whatever its hostname.
+const SITE: &str = "foolslide";
+
+/// Name and default value of each selector.
+pub(in crate::sites) const DEFAULTS: [(&str, &str); 3] = [
+    ("series_title", ".comic.info h1.title"),
+    ("chapter", ".list .element .title a"),
+    ("page_script", "script"),
+];
+
+/// CSS selectors to scrape the `FoOlSlide` readers.
+pub(super) struct Selectors {
+    /// Select the series title.
+    pub(super) series_title: kuchiki::Selectors,
+    /// Select chapter links in the chapter list.
+    pub(super) chapter: kuchiki::Selectors,
+    /// Select the scripts of the reader page, one of them holds the pages.
+    pub(super) page_script: kuchiki::Selectors,
+}
+
+impl Selectors {
+    /// Compile the selectors, applying the given overrides.
+    pub(super) fn new(overrides: &SelectorOverrides) -> Self {
+        let compile = |name| overrides.compile(SITE, name, &DEFAULTS);
+
+        Self {
+            series_title: compile("series_title"),
+            chapter: compile("chapter"),
+            page_script: compile("page_script"),
+        }
+    }
+
+    /// Run every selector, and the page list regexp, against `html`.
+    pub(super) fn check(&self, html: &kuchiki::NodeRef) -> Vec<SelectorCheck> {
+        // Inline scripts are too long to be reported, only check the regexp.
+        let scripts = self
+            .page_script
+            .filter(html.inclusive_descendants().elements())
+            .map(|script| script.text_contents())
+            .collect::<Vec<_>>();
+
+        vec![
+            SelectorCheck::run("series_title", &self.series_title, html, None),
+            SelectorCheck::run("chapter", &self.chapter, html, Some("href")),
+            SelectorCheck::run(
+                "page_script",
+                &self.page_script,
+                html,
+                Some("src"),
+            ),
+            SelectorCheck::run_regex(
+                "pages_regex",
+                &PAGES_REGEX,
+                scripts.iter().map(String::as_str),
+            ),
+        ]
+    }
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self::new(&SelectorOverrides::default())
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/foolslide/reader.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let checks = Selectors::default().check(&document);
+        let check = |name| {
+            checks
+                .iter()
+                .find(|check| check.name() == name)
+                .expect("check")
+        };
+
+        assert_eq!(check("series_title").matches(), 0);
+        assert_eq!(check("chapter").matches(), 0);
+        assert_eq!(check("pages_regex").matches(), 1);
+    }
+}
+
+// }}}
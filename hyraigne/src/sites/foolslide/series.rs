@@ -0,0 +1,72 @@
+use super::selectors::Selectors;
+use crate::{
+    types::Pagination,
+    Error,
+    Result,
+    Series,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Scrape series info from the given HTML, served at `url`.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    url: &Url,
+    selectors: &Selectors,
+) -> Result<Series> {
+    Ok(Series {
+        title: title_from_html(html, selectors)?,
+        // The series page doesn't advertise its URL.
+        url: url.clone(),
+        // No pagination here, everything is listed on the first page.
+        pagination: Pagination::new(0, 0),
+    })
+}
+
+/// Extract series title from the content of `<h1 class="title">`.
+#[allow(clippy::filter_next)]
+fn title_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<String> {
+    let raw_title = selectors
+        .series_title
+        .filter(html.descendants().elements())
+        .next()
+        .ok_or_else(|| Error::Scraping("series title not found".to_owned()))?
+        .text_contents();
+    let title = raw_title.trim();
+
+    if title.is_empty() {
+        return Err(Error::Scraping("series title is missing".to_owned()));
+    }
+    Ok(title.to_owned())
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/foolslide/series.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+        let url = Url::parse(
+            "https://reader.example.com/slide/series/moonlit-gardens/",
+        )
+        .unwrap();
+
+        let series =
+            scrape_from_html(&document, &url, &Selectors::default()).unwrap();
+
+        assert_eq!(series.title, "Moonlit Gardens");
+        assert_eq!(series.url, url);
+    }
+}
+
+// }}}
@@ -0,0 +1,165 @@
+use super::{
+    chapter,
+    page,
+    selectors::Selectors,
+    series,
+};
+use crate::{
+    sites::scraper::{
+        self,
+        ChapterLink,
+        Document,
+        Scraper,
+    },
+    Chapter,
+    Filter,
+    LinkKind,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// A web spider for a `FoOlSlide` reader.
+///
+/// Series are served at `/series/<series>/`, and chapters at
+/// `/read/<series>/<language>/<volume>/<chapter>/`.
+pub(crate) struct Site {
+    selectors: Selectors,
+}
+
+impl Site {
+    /// Initialize the web spider with the given options.
+    pub(crate) fn new(options: &Options) -> Self {
+        Self {
+            selectors: Selectors::new(&options.selectors),
+        }
+    }
+}
+
+impl Scraper for Site {
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        let html = kuchiki::parse_html().one(html);
+
+        Ok(self.selectors.check(&html))
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        chapter::parse_link(url).map_or(LinkKind::Series, |(_, kind)| kind)
+    }
+
+    fn parse_chapter_link(&self, url: &Url) -> Option<ChapterLink> {
+        chapter::parse_link(url).map(|(link, _)| link)
+    }
+
+    fn scrape_series(&self, url: &Url, document: &Document) -> Result<Series> {
+        series::scrape_from_html(document.html()?, url, &self.selectors)
+    }
+
+    fn scrape_chapters<'a>(
+        &self,
+        _url: &Url,
+        series: &'a Series,
+        _filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        chapter::scrape_from_html(document.html()?, series, &self.selectors)
+    }
+
+    fn keep_chapter(&self, chapter: &Chapter<'_>, filter: &Filter) -> bool {
+        scraper::keep_truncated_id(chapter, filter)
+    }
+
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        page::scrape_from_html(document.html()?, chapter, &self.selectors)
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::engine::Engine,
+        testing,
+        MemoryTransport,
+        Response,
+        Site as _,
+    };
+    use std::sync::Arc;
+
+    const SERIES_URL: &str =
+        "https://reader.example.com/slide/series/moonlit-gardens/";
+    const CHAPTER_URL: &str =
+        "https://reader.example.com/slide/read/moonlit-gardens/en/2/12/";
+
+    /// Build the blocking web spider.
+    fn spider(options: Options) -> Engine<Site> {
+        Engine::new(Site::new(&options), options)
+    }
+
+    #[test]
+    fn test_classify() {
+        let site = Site::new(&Options::new(0, 0, std::env::temp_dir()));
+        let classify = |url| site.classify(&Url::parse(url).unwrap());
+
+        assert_eq!(classify(SERIES_URL), LinkKind::Series);
+        assert_eq!(classify(CHAPTER_URL), LinkKind::Chapter);
+        assert_eq!(classify(&format!("{}page/4", CHAPTER_URL)), LinkKind::Page);
+    }
+
+    #[test]
+    fn test_download() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "foolslide/series.html");
+        testing::serve(&transport, CHAPTER_URL, "foolslide/reader.html");
+        let site = spider(testing::options(&transport, "foolslide-download"));
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        assert_eq!(series.title, "Moonlit Gardens");
+
+        let filter = Filter::new(12..=12, None, Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+        assert_eq!(chapters.len(), 1);
+
+        let pages = site.get_pages(&chapters[0]).unwrap();
+        assert_eq!(pages.len(), 6);
+
+        for page in &pages {
+            transport.insert(&page.main, Response::new(200, "image"));
+        }
+        site.mkdir(&chapters).unwrap();
+        site.download(&pages).unwrap();
+
+        for page in &pages {
+            let path = page.path(&site.output);
+            assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        }
+    }
+
+    #[test]
+    fn test_page_link() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "foolslide/series.html");
+        let site = spider(testing::options(&transport, "foolslide-link"));
+        let url = Url::parse(&format!("{}page/4", CHAPTER_URL)).unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        let chapter = site.get_chapter(&series, &url).unwrap();
+
+        assert_eq!(series.url.as_str(), SERIES_URL);
+        assert_eq!(chapter.id, 12.0);
+        assert_eq!(chapter.url.as_str(), CHAPTER_URL);
+    }
+}
+
+// }}}
@@ -0,0 +1,107 @@
+use super::SiteDefinition;
+use crate::{
+    Chapter,
+    Error,
+    Result,
+    Series,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Extract every chapter listed in the given HTML.
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    url: &Url,
+    series: &'a Series,
+    definition: &SiteDefinition,
+) -> Result<Vec<Chapter<'a>>> {
+    definition
+        .chapter
+        .filter(html.descendants().elements())
+        .map(|entry| {
+            let entry = entry.as_node();
+            let (id, volume) = parse_number(entry, definition)?;
+
+            Ok(Chapter {
+                id,
+                series,
+                volume,
+                url: definition.chapter_url.extract_url(entry, url)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Extract chapter ID and volume name (if any) from the chapter entry.
+fn parse_number(
+    html: &kuchiki::NodeRef,
+    definition: &SiteDefinition,
+) -> Result<(f64, Option<String>)> {
+    let number = definition.chapter_number.extract(html)?;
+    let matches = definition
+        .chapter_number_regex
+        .captures(&number)
+        .ok_or_else(|| {
+            Error::Scraping(format!(
+                "cannot match on chapter number: {}",
+                number
+            ))
+        })?;
+
+    let volume = matches
+        .name("volume")
+        .map(|m| format!("{:0>2}", m.as_str()));
+    let id = matches
+        .name("id")
+        .ok_or_else(|| {
+            Error::Scraping(format!("chapter ID not found in {}", number))
+        })?
+        .as_str()
+        .parse::<f64>()
+        .map_err(|err| {
+            Error::Scraping(format!("invalid chapter ID: {}", err))
+        })?;
+
+    Ok((id, volume))
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::generic::definition::load_fixture,
+        types::Pagination,
+    };
+    use std::path::PathBuf;
+
+    fn scrape(site: &str) -> usize {
+        let definition = load_fixture(site);
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push(format!("testdata/{}.com/series.html", site));
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        scrape_from_html(&document, &series.url, &series, &definition)
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_scraping_mangakakalot() {
+        assert_eq!(scrape("mangakakalot"), 330);
+    }
+
+    #[test]
+    fn test_scraping_webtoonscan() {
+        assert_eq!(scrape("webtoonscan"), 79);
+    }
+}
+
+// }}}
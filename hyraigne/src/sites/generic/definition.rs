@@ -0,0 +1,554 @@
+//! Site definitions, loaded from TOML files.
+
+use crate::{
+    Error,
+    Result,
+    SelectorCheck,
+};
+use kuchiki::traits::*;
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+use url::Url;
+
+/// A reference to an HTML element.
+pub(super) type ElementRef = kuchiki::NodeDataRef<kuchiki::ElementData>;
+
+/// A declarative description of a website, used by the generic web spider.
+///
+/// # Example
+///
+/// ```toml
+/// name = "mangakakalot"
+/// hosts = ["mangakakalot.com"]
+///
+/// [series]
+/// title = ".manga-info-text h1"
+///
+/// [chapters]
+/// selector = ".chapter-list .row a"
+/// number_attribute = "title"
+/// number_regex = '(?i)(?:Vol.(?P<volume>\d+) )?Chapter (?P<id>\d+(?:\.\d+)?)'
+///
+/// [pages]
+/// selector = ".container-chapter-reader img"
+/// url_attribute = "src"
+/// ```
+pub struct SiteDefinition {
+    /// Name of the website.
+    pub(super) name: String,
+    /// Hostnames served by the website.
+    pub(super) hosts: Vec<String>,
+    /// Referer to use when downloading images (default to the chapter URL).
+    pub(super) referer: Option<Url>,
+    /// Cookies to send with every request.
+    pub(super) cookies: BTreeMap<String, String>,
+    /// Series title.
+    pub(super) series_title: Selector,
+    /// Canonical series URL, if any (default to the requested URL).
+    pub(super) series_url: Option<Selector>,
+    /// Chapter entries in the chapter list.
+    pub(super) chapter: kuchiki::Selectors,
+    /// Chapter URL, inside a chapter entry.
+    pub(super) chapter_url: Selector,
+    /// Chapter number (and volume), inside a chapter entry.
+    pub(super) chapter_number: Selector,
+    /// Regexp to extract the chapter number and volume.
+    pub(super) chapter_number_regex: Regex,
+    /// How the chapter list is paginated.
+    pub(super) pagination: Pagination,
+    /// Image URLs, in the chapter page.
+    pub(super) page_url: Selector,
+}
+
+impl SiteDefinition {
+    /// Parse and validate a site definition.
+    pub fn from_toml(definition: &str) -> Result<Self> {
+        let raw: RawDefinition = toml::from_str(definition).map_err(|err| {
+            Error::Config(format!("invalid site definition: {}", err))
+        })?;
+
+        Self::compile(raw)
+    }
+
+    /// Load a site definition from the TOML file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let definition = fs::read_to_string(path).map_err(|err| {
+            Error::Filesystem {
+                operation: "read",
+                target: path.to_path_buf(),
+                source: err,
+            }
+        })?;
+
+        Self::from_toml(&definition).map_err(|err| {
+            match err {
+                Error::Config(msg) => {
+                    Error::Config(format!("{}: {}", path.display(), msg))
+                },
+                err => err,
+            }
+        })
+    }
+
+    /// Name of the website.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Hostnames served by the website.
+    #[must_use]
+    pub fn hosts(&self) -> &[String] {
+        &self.hosts
+    }
+
+    /// Run every selector, and the chapter number regexp, against `html`.
+    pub(super) fn check(&self, html: &kuchiki::NodeRef) -> Vec<SelectorCheck> {
+        let chapters = self
+            .chapter
+            .filter(html.inclusive_descendants().elements())
+            .collect::<Vec<_>>();
+        let numbers = self
+            .chapter_number
+            .check_nested("chapter_number", &chapters);
+
+        let mut checks = vec![self.series_title.check("series_title", html)];
+        if let Some(ref series_url) = self.series_url {
+            checks.push(series_url.check("series_url", html));
+        }
+        checks.push(SelectorCheck::run("chapter", &self.chapter, html, None));
+        checks.push(self.chapter_url.check_nested("chapter_url", &chapters));
+        checks.push(SelectorCheck::run_regex(
+            "chapter_number_regex",
+            &self.chapter_number_regex,
+            numbers.values().iter().map(String::as_str),
+        ));
+        checks.push(numbers);
+        checks.push(self.page_url.check("page_url", html));
+
+        checks
+    }
+
+    /// Compile the selectors and regexp of a raw definition.
+    fn compile(raw: RawDefinition) -> Result<Self> {
+        let RawDefinition {
+            name,
+            hosts,
+            referer,
+            cookies,
+            series,
+            chapters,
+            pages,
+        } = raw;
+
+        if hosts.is_empty() {
+            return Err(Error::Config(format!(
+                "no host specified for {}",
+                name
+            )));
+        }
+
+        let referer = referer
+            .map(|referer| {
+                Url::parse(&referer).map_err(|err| {
+                    Error::Config(format!(
+                        "invalid referer `{}`: {}",
+                        referer, err
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let series_url_attribute = series.url_attribute;
+        let series_url = series
+            .url
+            .map(|url| {
+                Selector::new("series URL", &url, Some(series_url_attribute))
+            })
+            .transpose()?;
+
+        let chapter_number_regex =
+            Regex::new(&chapters.number_regex).map_err(|err| {
+                Error::Config(format!("invalid chapter number regexp: {}", err))
+            })?;
+        if !chapter_number_regex
+            .capture_names()
+            .any(|name| name == Some("id"))
+        {
+            return Err(Error::Config(
+                "chapter number regexp must capture an `id` group".to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            name,
+            hosts,
+            referer,
+            cookies,
+            series_title: Selector::new(
+                "series title",
+                &series.title,
+                series.title_attribute,
+            )?,
+            series_url,
+            chapter: compile_selector("chapter", &chapters.selector)?,
+            chapter_url: Selector::new(
+                "chapter URL",
+                chapters.url.as_deref().unwrap_or(":scope"),
+                Some(chapters.url_attribute),
+            )?,
+            chapter_number: Selector::new(
+                "chapter number",
+                chapters.number.as_deref().unwrap_or(":scope"),
+                chapters.number_attribute,
+            )?,
+            chapter_number_regex,
+            pagination: chapters.pagination,
+            page_url: Selector::new(
+                "page URL",
+                &pages.selector,
+                Some(pages.url_attribute),
+            )?,
+        })
+    }
+}
+
+/// A CSS selector, and where to read the value in the selected element.
+pub(super) struct Selector {
+    /// What is selected, for error messages.
+    pub(super) what: &'static str,
+    /// The selector itself, `None` to select the current element.
+    pub(super) selectors: Option<kuchiki::Selectors>,
+    /// Attribute holding the value, `None` for the text content.
+    pub(super) attribute: Option<String>,
+}
+
+impl Selector {
+    /// Compile a selector.
+    ///
+    /// `:scope` selects the current element.
+    fn new(
+        what: &'static str,
+        selector: &str,
+        attribute: Option<String>,
+    ) -> Result<Self> {
+        let selectors = if selector == ":scope" {
+            None
+        } else {
+            Some(compile_selector(what, selector)?)
+        };
+
+        Ok(Self {
+            what,
+            selectors,
+            attribute,
+        })
+    }
+
+    /// Run the selector against `html`.
+    fn check(&self, name: &str, html: &kuchiki::NodeRef) -> SelectorCheck {
+        self.check_elements(name, &self.select(html).collect::<Vec<_>>())
+    }
+
+    /// Run the selector inside each element of `parents`.
+    ///
+    /// Only the first match inside each parent element is considered.
+    fn check_nested(
+        &self,
+        name: &str,
+        parents: &[ElementRef],
+    ) -> SelectorCheck {
+        self.check_elements(
+            name,
+            &parents
+                .iter()
+                .filter_map(|parent| self.select(parent.as_node()).next())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Report the values of the selected elements.
+    fn check_elements(
+        &self,
+        name: &str,
+        elements: &[ElementRef],
+    ) -> SelectorCheck {
+        SelectorCheck {
+            name: name.to_owned(),
+            pattern: self
+                .selectors
+                .as_ref()
+                .map_or_else(|| ":scope".to_owned(), ToString::to_string),
+            matches: elements.len(),
+            values: elements
+                .iter()
+                .filter_map(|element| self.value(element).ok())
+                .collect(),
+        }
+    }
+
+    /// Return the elements selected in `html`.
+    pub(super) fn select<'a>(
+        &'a self,
+        html: &kuchiki::NodeRef,
+    ) -> Box<dyn Iterator<Item = ElementRef> + 'a> {
+        match self.selectors {
+            Some(ref selectors) => {
+                Box::new(
+                    selectors.filter(html.inclusive_descendants().elements()),
+                )
+            },
+            None => Box::new(html.clone().into_element_ref().into_iter()),
+        }
+    }
+
+    /// Extract the value of the first element selected in `html`.
+    pub(super) fn extract(&self, html: &kuchiki::NodeRef) -> Result<String> {
+        let element = self.select(html).next().ok_or_else(|| {
+            Error::Scraping(format!("{} not found", self.what))
+        })?;
+
+        self.value(&element)
+    }
+
+    /// Extract the value of the first element selected in `html`, as an URL.
+    ///
+    /// Relative URLs are resolved against `base`.
+    pub(super) fn extract_url(
+        &self,
+        html: &kuchiki::NodeRef,
+        base: &Url,
+    ) -> Result<Url> {
+        let element = self.select(html).next().ok_or_else(|| {
+            Error::Scraping(format!("{} not found", self.what))
+        })?;
+
+        self.url_value(&element, base)
+    }
+
+    /// Read the value from a selected element.
+    pub(super) fn value(&self, element: &ElementRef) -> Result<String> {
+        let value = match self.attribute {
+            Some(ref attribute) => {
+                element
+                    .attributes
+                    .borrow()
+                    .get(attribute.as_str())
+                    .map(str::to_owned)
+            },
+            None => Some(element.text_contents()),
+        }
+        .map(|value| value.trim().to_owned())
+        .unwrap_or_default();
+
+        if value.is_empty() {
+            return Err(Error::Scraping(format!("{} is missing", self.what)));
+        }
+        Ok(value)
+    }
+
+    /// Read the value from a selected element, as an URL.
+    ///
+    /// Relative URLs are resolved against `base`.
+    pub(super) fn url_value(
+        &self,
+        element: &ElementRef,
+        base: &Url,
+    ) -> Result<Url> {
+        let url = self.value(element)?;
+
+        base.join(&url).map_err(|err| {
+            Error::Scraping(format!("invalid {} `{}`: {}", self.what, url, err))
+        })
+    }
+}
+
+/// How the chapter list is paginated.
+#[derive(Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(super) enum Pagination {
+    /// Every chapter is listed on the series page.
+    #[default]
+    None,
+
+    /// Chapter list pages are selected with a query parameter.
+    Query {
+        /// Name of the query parameter.
+        parameter: String,
+    },
+}
+
+/// Compile a CSS selector.
+fn compile_selector(what: &str, selector: &str) -> Result<kuchiki::Selectors> {
+    kuchiki::Selectors::compile(selector).map_err(|()| {
+        Error::Config(format!("invalid {} selector `{}`", what, selector))
+    })
+}
+
+// Raw definition {{{
+
+/// Site definition, as written in the TOML file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawDefinition {
+    name: String,
+    hosts: Vec<String>,
+    referer: Option<String>,
+    #[serde(default)]
+    cookies: BTreeMap<String, String>,
+    series: RawSeries,
+    chapters: RawChapters,
+    pages: RawPages,
+}
+
+/// How to scrape the series page.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSeries {
+    title: String,
+    title_attribute: Option<String>,
+    url: Option<String>,
+    #[serde(default = "default_url_attribute")]
+    url_attribute: String,
+}
+
+/// How to scrape the chapter list.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawChapters {
+    selector: String,
+    url: Option<String>,
+    #[serde(default = "default_link_attribute")]
+    url_attribute: String,
+    number: Option<String>,
+    number_attribute: Option<String>,
+    number_regex: String,
+    #[serde(default)]
+    pagination: Pagination,
+}
+
+/// How to scrape the chapter page.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPages {
+    selector: String,
+    #[serde(default = "default_image_attribute")]
+    url_attribute: String,
+}
+
+fn default_url_attribute() -> String {
+    "content".to_owned()
+}
+
+fn default_link_attribute() -> String {
+    "href".to_owned()
+}
+
+fn default_image_attribute() -> String {
+    "src".to_owned()
+}
+
+// }}}
+
+// Tests {{{
+
+#[cfg(test)]
+pub(super) fn load_fixture(name: &str) -> SiteDefinition {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata/definitions");
+    path.push(format!("{}.toml", name));
+
+    SiteDefinition::load(&path).expect("valid site definition")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let definition = load_fixture("mangakakalot");
+
+        assert_eq!(definition.name(), "mangakakalot");
+        assert_eq!(definition.hosts(), ["mangakakalot.com"]);
+        assert!(matches!(definition.pagination, Pagination::None));
+    }
+
+    #[test]
+    fn test_check() {
+        let definition = load_fixture("webtoonscan");
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/webtoonscan.com/series.html");
+        let html = fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let checks = definition.check(&document);
+        let check = |name| {
+            checks
+                .iter()
+                .find(|check| check.name() == name)
+                .expect("check")
+        };
+
+        assert_eq!(
+            check("series_title").values(),
+            ["Legend Of The Northern Blade"]
+        );
+        assert_eq!(check("chapter").matches(), 79);
+        assert_eq!(check("chapter_url").matches(), 79);
+        assert_eq!(check("chapter_number_regex").matches(), 79);
+        assert_eq!(check("page_url").matches(), 0);
+    }
+
+    #[test]
+    fn test_invalid_selector() {
+        let definition = r#"
+            name = "broken"
+            hosts = ["example.com"]
+
+            [series]
+            title = "h1["
+
+            [chapters]
+            selector = "a"
+            number_regex = '(?P<id>\d+)'
+
+            [pages]
+            selector = "img"
+        "#;
+
+        let err = SiteDefinition::from_toml(definition).err().unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid configuration: invalid series title selector `h1[`"
+        );
+    }
+
+    #[test]
+    fn test_missing_id_group() {
+        let definition = r#"
+            name = "broken"
+            hosts = ["example.com"]
+
+            [series]
+            title = "h1"
+
+            [chapters]
+            selector = "a"
+            number_regex = '\d+'
+
+            [pages]
+            selector = "img"
+        "#;
+
+        assert!(SiteDefinition::from_toml(definition).is_err());
+    }
+}
+
+// }}}
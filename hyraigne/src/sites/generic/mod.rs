@@ -0,0 +1,15 @@
+//! A generic web spider, driven by a declarative site definition.
+//!
+//! Most websites only need a handful of CSS selectors and a regexp to be
+//! scraped, such websites can be described in a TOML file instead of having a
+//! dedicated web spider.
+
+mod chapter;
+mod definition;
+mod page;
+mod series;
+mod site;
+
+pub use definition::SiteDefinition;
+
+pub(crate) use site::Site;
@@ -0,0 +1,78 @@
+use super::SiteDefinition;
+use crate::{
+    Chapter,
+    Page,
+    Result,
+};
+
+/// Scrape page links from the chapter's page HTML.
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    chapter: &'a Chapter<'_>,
+    definition: &SiteDefinition,
+) -> Result<Vec<Page<'a>>> {
+    definition
+        .page_url
+        .select(html)
+        .enumerate()
+        .map(|(idx, node)| {
+            #[allow(clippy::cast_possible_truncation)] // Page number is small.
+            Ok(Page {
+                id: (idx + 1) as u16,
+                chapter,
+                main: definition.page_url.url_value(&node, &chapter.url)?,
+                fallbacks: Vec::new(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::generic::definition::load_fixture,
+        types::Pagination,
+        Series,
+    };
+    use kuchiki::traits::*;
+    use std::path::PathBuf;
+    use url::Url;
+
+    fn scrape(site: &str) -> usize {
+        let definition = load_fixture(site);
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let chapter = Chapter {
+            id: 42.0,
+            series: &series,
+            volume: None,
+            url: Url::parse("http://example.com/42/").unwrap(),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push(format!("testdata/{}.com/chapter.html", site));
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        scrape_from_html(&document, &chapter, &definition)
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_scraping_mangakakalot() {
+        assert_eq!(scrape("mangakakalot"), 23);
+    }
+
+    #[test]
+    fn test_scraping_webtoonscan() {
+        assert_eq!(scrape("webtoonscan"), 19);
+    }
+}
+
+// }}}
@@ -0,0 +1,70 @@
+use super::SiteDefinition;
+use crate::{
+    types::Pagination,
+    Result,
+    Series,
+};
+use url::Url;
+
+/// Scrape series info from the given HTML.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    url: &Url,
+    definition: &SiteDefinition,
+) -> Result<Series> {
+    let url = match definition.series_url {
+        Some(ref selector) => selector.extract_url(html, url)?,
+        None => url.clone(),
+    };
+
+    Ok(Series {
+        title: definition.series_title.extract(html)?,
+        url,
+        // Pagination is handled by the site definition.
+        pagination: Pagination::new(0, 0),
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites::generic::definition::load_fixture;
+    use kuchiki::traits::*;
+    use std::path::PathBuf;
+
+    fn scrape(site: &str, url: &str) -> Series {
+        let definition = load_fixture(site);
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push(format!("testdata/{}.com/series.html", site));
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+        let url = Url::parse(url).unwrap();
+
+        scrape_from_html(&document, &url, &definition).unwrap()
+    }
+
+    #[test]
+    fn test_scraping_mangakakalot() {
+        let series = scrape("mangakakalot", "https://mangakakalot.com/");
+
+        assert_eq!(series.title, "Higanjima");
+        assert_eq!(
+            series.url.as_str(),
+            "https://mangakakalot.com/read-lu8jl158504848312"
+        );
+    }
+
+    #[test]
+    fn test_scraping_webtoonscan() {
+        let url =
+            "https://webtoonscan.com/manhwa/legend-of-the-northern-blade/";
+        let series = scrape("webtoonscan", url);
+
+        assert_eq!(series.title, "Legend Of The Northern Blade");
+        assert_eq!(series.url.as_str(), url);
+    }
+}
+
+// }}}
@@ -0,0 +1,190 @@
+use super::{
+    chapter,
+    definition::Pagination,
+    page,
+    series,
+    SiteDefinition,
+};
+use crate::{
+    sites::scraper::{
+        Document,
+        Scraper,
+    },
+    Chapter,
+    Filter,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+};
+use cookie_store::CookieStore;
+use kuchiki::traits::*;
+use std::sync::Arc;
+use url::Url;
+
+/// A web spider driven by a site definition.
+pub(crate) struct Site {
+    definition: Arc<SiteDefinition>,
+}
+
+impl Site {
+    /// Initialize the web spider for the given site definition.
+    pub(crate) fn new(definition: Arc<SiteDefinition>) -> Self {
+        Self { definition }
+    }
+}
+
+impl Scraper for Site {
+    fn cookies(&self, options: &Options) -> Option<CookieStore> {
+        cookie_store_from_definition(&self.definition, options)
+    }
+
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        let html = kuchiki::parse_html().one(html);
+
+        Ok(self.definition.check(&html))
+    }
+
+    fn scrape_series(&self, url: &Url, document: &Document) -> Result<Series> {
+        series::scrape_from_html(document.html()?, url, &self.definition)
+    }
+
+    fn chapters_url(
+        &self,
+        series: &Series,
+        _filter: &Filter,
+        index: u16,
+    ) -> Option<Url> {
+        match self.definition.pagination {
+            Pagination::None => {
+                if index == 0 {
+                    Some(series.url.clone())
+                } else {
+                    None
+                }
+            },
+            // We don't know the page count: the engine stops on the first
+            // page that doesn't bring new chapters.
+            Pagination::Query { ref parameter } => {
+                let mut url = series.url.clone();
+                url.query_pairs_mut()
+                    .append_pair(parameter, &(index + 1).to_string());
+
+                Some(url)
+            },
+        }
+    }
+
+    fn scrape_chapters<'a>(
+        &self,
+        url: &Url,
+        series: &'a Series,
+        _filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        chapter::scrape_from_html(
+            document.html()?,
+            url,
+            series,
+            &self.definition,
+        )
+    }
+
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        page::scrape_from_html(document.html()?, chapter, &self.definition)
+    }
+
+    fn referer<'a>(&'a self, page: &'a Page<'_>) -> &'a Url {
+        self.definition
+            .referer
+            .as_ref()
+            .unwrap_or(&page.chapter.url)
+    }
+}
+
+/// Build a cookie store holding the cookies required by the site definition.
+///
+/// Cookies are set on the remapped hosts, if any.
+fn cookie_store_from_definition(
+    definition: &SiteDefinition,
+    options: &Options,
+) -> Option<CookieStore> {
+    if definition.cookies.is_empty() {
+        return None;
+    }
+
+    let mut store = CookieStore::default();
+    for host in &definition.hosts {
+        let url = match Url::parse(&format!("https://{}/", host)) {
+            Ok(url) => options.base_urls.rewrite(&url),
+            Err(err) => {
+                log::warn!("cannot set cookies for {}: {}", host, err);
+                continue;
+            },
+        };
+
+        for (name, value) in &definition.cookies {
+            let cookie = ureq::Cookie::new(name.as_str(), value.as_str());
+            if let Err(err) = store.insert_raw(&cookie, &url) {
+                log::warn!("cannot set cookie {} for {}: {}", name, host, err);
+            }
+        }
+    }
+
+    Some(store)
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::engine::Engine,
+        testing,
+        MemoryTransport,
+        Site as _,
+    };
+
+    const SERIES_URL: &str = "https://mangakakalot.com/read-lu8jl158504848312";
+
+    #[test]
+    fn test_query_pagination() {
+        let mut definition = String::from_utf8(testing::fixture(
+            "definitions/mangakakalot.toml",
+        ))
+        .unwrap();
+        definition.push_str(
+            "\n[chapters.pagination]\ntype = \"query\"\nparameter = \"page\"\n",
+        );
+        let definition = SiteDefinition::from_toml(&definition).unwrap();
+        let transport = Arc::new(MemoryTransport::new());
+        for url in &[
+            SERIES_URL.to_owned(),
+            format!("{}?page=1", SERIES_URL),
+            format!("{}?page=2", SERIES_URL),
+        ] {
+            testing::serve(&transport, url, "mangakakalot.com/series.html");
+        }
+        let site = Engine::new(
+            Site::new(Arc::new(definition)),
+            testing::options(&transport, "generic-pagination"),
+        );
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        let filter = Filter::new(0..=u16::MAX, None, Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+
+        assert_eq!(chapters.len(), 330);
+        // The second page doesn't bring anything new: stop there.
+        assert_eq!(transport.requests().len(), 3);
+    }
+}
+
+// }}}
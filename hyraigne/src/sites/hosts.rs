@@ -0,0 +1,95 @@
+//! Hostname aliases: bare and mobile domains, and mirrors.
+
+use url::Url;
+
+/// Prefixes of the hostnames that serve the same website.
+const PREFIXES: [&str; 2] = ["www.", "m."];
+
+/// Return `host` without its `www.` or `m.` prefix, if any.
+fn bare(host: &str) -> &str {
+    PREFIXES
+        .iter()
+        .find_map(|prefix| host.strip_prefix(prefix))
+        .unwrap_or(host)
+}
+
+/// Test if `a` and `b` are the same website, regardless of their `www.` or
+/// `m.` prefix (case-insensitive).
+pub(super) fn same(a: &str, b: &str) -> bool {
+    bare(&a.to_ascii_lowercase()) == bare(&b.to_ascii_lowercase())
+}
+
+/// Move `url` to `canonical` if it's served from an alias of `canonical` (a
+/// bare or mobile domain) or from one of its `mirrors`.
+///
+/// Other URLs (e.g. on a remapped base URL) are left as is.
+pub(super) fn canonicalize(
+    url: &Url,
+    canonical: &str,
+    mirrors: &[&str],
+) -> Url {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return url.clone(),
+    };
+    if host == canonical
+        || !(same(host, canonical)
+            || mirrors.iter().any(|mirror| same(host, mirror)))
+    {
+        return url.clone();
+    }
+
+    let mut canonical_url = url.clone();
+    match canonical_url.set_host(Some(canonical)) {
+        Ok(()) => canonical_url,
+        Err(err) => {
+            log::warn!(
+                "cannot move {} to {}: {}",
+                url.as_str(),
+                canonical,
+                err
+            );
+            url.clone()
+        },
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same() {
+        assert!(same("www.webtoons.com", "webtoons.com"));
+        assert!(same("m.webtoons.com", "www.webtoons.com"));
+        assert!(same("WWW.MangaDex.org", "mangadex.org"));
+        assert!(!same("webtoons.com", "webtoonscan.com"));
+        assert!(!same("api.mangadex.org", "mangadex.org"));
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let canonicalize = |url| {
+            let url = Url::parse(url).unwrap();
+            canonicalize(&url, "www.webtoons.com", &["webtoons.example"])
+                .to_string()
+        };
+
+        assert_eq!(
+            canonicalize("https://m.webtoons.com/en/top?x=1"),
+            "https://www.webtoons.com/en/top?x=1"
+        );
+        assert_eq!(
+            canonicalize("https://webtoons.example/en/top"),
+            "https://www.webtoons.com/en/top"
+        );
+        assert_eq!(
+            canonicalize("http://127.0.0.1:8080/en/top"),
+            "http://127.0.0.1:8080/en/top"
+        );
+    }
+}
+
+// }}}
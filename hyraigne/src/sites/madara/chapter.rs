@@ -0,0 +1,210 @@
+use super::{
+    config::Config,
+    selectors::Selectors,
+};
+use crate::{
+    spiders::Request,
+    Chapter,
+    Error,
+    Result,
+    Series,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Extract every chapter listed in the given HTML.
+#[allow(clippy::filter_map)]
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    series: &'a Series,
+    config: &Config,
+    selectors: &Selectors,
+) -> Result<Vec<Chapter<'a>>> {
+    selectors
+        .chapter
+        .filter(html.descendants().elements())
+        .map(|link| {
+            let url = url_from_element(&link)?;
+            let id = id_from_url(&url, config)?;
+            let volume = None;
+
+            Ok(Chapter {
+                id,
+                series,
+                volume,
+                url,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Build the request loading the chapter list of the series page `html`.
+///
+/// Madara can load the chapter list with JavaScript, through the `WordPress`
+/// AJAX endpoint, instead of listing them in the page.
+/// `None` is returned if the series ID isn't found.
+#[allow(clippy::filter_next)]
+pub(super) fn request_from_html(
+    html: &kuchiki::NodeRef,
+    config: &Config,
+    selectors: &Selectors,
+) -> Result<Option<Request>> {
+    let element = match selectors
+        .post_id
+        .filter(html.descendants().elements())
+        .next()
+    {
+        Some(element) => element,
+        None => return Ok(None),
+    };
+    let attributes = element.attributes.borrow();
+    let id = attributes
+        .get("data-id")
+        .or_else(|| attributes.get("value"))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| Error::Scraping("series ID is missing".to_owned()))?;
+
+    let url =
+        config
+            .base_url()
+            .join("wp-admin/admin-ajax.php")
+            .map_err(|err| {
+                Error::Scraping(format!("invalid AJAX endpoint: {}", err))
+            })?;
+
+    Ok(Some(Request::post(
+        &url,
+        &[("action", "manga_get_chapters"), ("manga", id)],
+    )))
+}
+
+/// Extract chapter ID from chapter's URL.
+///
+/// The last path segment is the chapter slug: the chapter number, with dashes
+/// instead of dots, behind the prefix of the website (e.g. `chapter-42-5`).
+pub(super) fn id_from_url(url: &Url, config: &Config) -> Result<f64> {
+    let slug = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .ok_or_else(|| Error::Scraping("chapter ID not found".to_owned()))?;
+
+    slug.strip_prefix(config.quirks.chapter_prefix.as_ref())
+        .ok_or_else(|| {
+            Error::Scraping(format!("unexpected chapter slug: {}", slug))
+        })?
+        .replace('-', ".")
+        .parse::<f64>()
+        .map_err(|err| Error::Scraping(format!("invalid chapter ID: {}", err)))
+}
+
+/// Extract the chapter URL.
+fn url_from_element(element: &kuchiki::ElementData) -> Result<Url> {
+    let attributes = element.attributes.borrow();
+    let url = attributes
+        .get("href")
+        .ok_or_else(|| Error::Scraping("chapter URL not found".to_owned()))?;
+
+    Url::parse(url).map_err(|err| {
+        Error::Scraping(format!("invalid chapter URL `{}`: {}", url, err))
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::madara::{
+            MadaraQuirks,
+            SITES,
+        },
+        types::Pagination,
+    };
+    use std::path::PathBuf;
+
+    /// Parse the fixture at `testdata/webtoonscan.com/<name>`.
+    fn load(name: &str) -> kuchiki::NodeRef {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/webtoonscan.com");
+        path.push(name);
+        let html = std::fs::read_to_string(&path).expect("test data");
+
+        kuchiki::parse_html().one(html)
+    }
+
+    #[test]
+    fn test_scraping() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let document = load("series.html");
+
+        let chapters = scrape_from_html(
+            &document,
+            &series,
+            SITES[0],
+            &Selectors::default(),
+        )
+        .unwrap();
+
+        assert_eq!(chapters.len(), 79);
+        assert_eq!(chapters[0].id, 79.0);
+    }
+
+    #[test]
+    fn test_request_scraping() {
+        let document = load("series-ajax.html");
+
+        let request =
+            request_from_html(&document, SITES[0], &Selectors::default())
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "https://webtoonscan.com/wp-admin/admin-ajax.php"
+        );
+        assert_eq!(
+            request.body(),
+            Some(&b"action=manga_get_chapters&manga=709"[..])
+        );
+        assert!(request_from_html(
+            &load("chapter.html"),
+            SITES[0],
+            &Selectors::default()
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn test_id_from_url() {
+        let config = Config::new(
+            "example",
+            "https://madara.example/",
+            MadaraQuirks::new(),
+        );
+        let id_from_url =
+            |url| id_from_url(&Url::parse(url).unwrap(), &config).ok();
+
+        assert_eq!(
+            id_from_url("https://madara.example/manga/title/chapter-42/"),
+            Some(42.0)
+        );
+        assert_eq!(
+            id_from_url("https://madara.example/manga/title/chapter-42-5"),
+            Some(42.5)
+        );
+        assert_eq!(id_from_url("https://madara.example/manga/title/42/"), None);
+
+        let url =
+            Url::parse("https://webtoonscan.com/manhwa/title/42/").unwrap();
+        assert_eq!(super::id_from_url(&url, SITES[0]).ok(), Some(42.0));
+    }
+}
+
+// }}}
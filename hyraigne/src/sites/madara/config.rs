@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+use url::Url;
+
+/// The built-in Madara websites.
+pub(in crate::sites) static SITES: [&Config; 1] = [&WEBTOONSCAN];
+
+/// `https://webtoonscan.com`
+static WEBTOONSCAN: Config = Config::new(
+    "webtoonscan",
+    "https://webtoonscan.com/",
+    // Chapters are only numbered: `/manhwa/title/42/`.
+    MadaraQuirks::builtin("manhwa", ""),
+);
+
+/// How the URLs of a Madara website depart from the default layout
+/// (`/manga/title/chapter-42/`).
+///
+/// # Example
+///
+/// ```
+/// use hyraigne::MadaraQuirks;
+///
+/// // Series under `/manhwa/title/`, chapters under `/manhwa/title/42/`.
+/// let quirks = MadaraQuirks::new()
+///     .with_series_path("manhwa")
+///     .with_chapter_prefix("");
+/// ```
+#[derive(Clone, Debug)]
+pub struct MadaraQuirks {
+    /// First segment of the series paths (e.g. `manga` in `/manga/title/`).
+    pub(super) series_path: Cow<'static, str>,
+    /// Prefix of the chapter slugs, before the chapter number.
+    pub(super) chapter_prefix: Cow<'static, str>,
+}
+
+impl MadaraQuirks {
+    /// Follow the default URL layout.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::builtin("manga", "chapter-")
+    }
+
+    /// Series under `/<series_path>/`, chapters numbered after
+    /// `chapter_prefix`.
+    pub(super) const fn builtin(
+        series_path: &'static str,
+        chapter_prefix: &'static str,
+    ) -> Self {
+        Self {
+            series_path: Cow::Borrowed(series_path),
+            chapter_prefix: Cow::Borrowed(chapter_prefix),
+        }
+    }
+
+    /// Serve the series under `/<path>/` instead of `/manga/`.
+    #[must_use]
+    pub fn with_series_path(self, path: &str) -> Self {
+        Self {
+            series_path: Cow::Owned(path.to_owned()),
+            ..self
+        }
+    }
+
+    /// Prefix the chapter numbers with `prefix` instead of `chapter-`.
+    #[must_use]
+    pub fn with_chapter_prefix(self, prefix: &str) -> Self {
+        Self {
+            chapter_prefix: Cow::Owned(prefix.to_owned()),
+            ..self
+        }
+    }
+}
+
+impl Default for MadaraQuirks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What sets a Madara website apart from the others.
+#[derive(Clone, Debug)]
+pub(in crate::sites) struct Config {
+    /// Name of the website.
+    pub(in crate::sites) name: Cow<'static, str>,
+    /// Section of the selector overrides applying to the website.
+    pub(super) overrides: &'static str,
+    /// Base URL of the website.
+    base_url: Cow<'static, str>,
+    /// URL layout of the website.
+    pub(super) quirks: MadaraQuirks,
+}
+
+impl Config {
+    /// Describe the built-in website called `name`, served at `base_url`.
+    ///
+    /// Its selectors are overridden under its own name.
+    pub(super) const fn new(
+        name: &'static str,
+        base_url: &'static str,
+        quirks: MadaraQuirks,
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            overrides: name,
+            base_url: Cow::Borrowed(base_url),
+            quirks,
+        }
+    }
+
+    /// Describe the website called `name`, served at `base_url`.
+    ///
+    /// The selectors of such websites are overridden under `madara`.
+    pub(in crate::sites) fn custom(
+        name: &str,
+        base_url: &Url,
+        quirks: MadaraQuirks,
+    ) -> Self {
+        Self {
+            name: Cow::Owned(name.to_owned()),
+            overrides: "madara",
+            base_url: Cow::Owned(base_url.as_str().to_owned()),
+            quirks,
+        }
+    }
+
+    /// Base URL of the website.
+    #[allow(clippy::expect_used)] // Hardcoded or already parsed, hence valid.
+    pub(super) fn base_url(&self) -> Url {
+        Url::parse(&self.base_url).expect("valid base URL")
+    }
+
+    /// Hostname of the website.
+    pub(in crate::sites) fn host(&self) -> String {
+        self.base_url().host_str().unwrap_or_default().to_owned()
+    }
+}
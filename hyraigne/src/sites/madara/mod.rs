@@ -0,0 +1,20 @@
+//! Web spiders for the websites built on Madara, a manga theme for `WordPress`.
+//!
+//! Those websites share the same markup and URL layout, so supporting a new one
+//! only takes a `Config` describing its few quirks.
+
+mod chapter;
+mod config;
+mod page;
+mod search;
+mod selectors;
+mod series;
+mod site;
+
+pub use config::MadaraQuirks;
+pub(super) use config::{
+    Config,
+    SITES,
+};
+pub(super) use selectors::DEFAULTS as DEFAULT_SELECTORS;
+pub(crate) use site::Site;
@@ -1,4 +1,4 @@
-use super::selectors::PAGE_URL_SELECTOR;
+use super::selectors::Selectors;
 use crate::{
     Chapter,
     Error,
@@ -8,19 +8,30 @@ use crate::{
 use kuchiki::traits::*;
 use url::Url;
 
+/// Attributes holding the image URL, by order of preference.
+const IMAGE_ATTRIBUTES: [&str; 3] = ["data-src", "data-lazy-src", "src"];
+
 /// Scrape page links from the chapter's page HTML.
 pub(super) fn scrape_from_html<'a>(
     html: &kuchiki::NodeRef,
     chapter: &'a Chapter<'_>,
+    selectors: &Selectors,
 ) -> Result<Vec<Page<'a>>> {
-    PAGE_URL_SELECTOR
+    selectors
+        .page_url
         .filter(html.descendants().elements())
         .enumerate()
         .map(|(idx, node)| {
             let attributes = node.attributes.borrow();
-            let url = attributes.get("src").ok_or_else(|| {
-                Error::Scraping("page URL not found".to_owned())
-            })?;
+            // Lazy-loaded images only get their `src` through JavaScript.
+            let url = IMAGE_ATTRIBUTES
+                .iter()
+                .filter_map(|&name| attributes.get(name))
+                .map(str::trim)
+                .find(|url| !url.is_empty())
+                .ok_or_else(|| {
+                    Error::Scraping("page URL not found".to_owned())
+                })?;
 
             let url = Url::parse(url).map_err(|err| {
                 Error::Scraping(format!("invalid page URL `{}`: {}", url, err))
@@ -31,7 +42,7 @@ pub(super) fn scrape_from_html<'a>(
                 id: (idx + 1) as u16,
                 chapter,
                 main: url,
-                fallback: None,
+                fallbacks: Vec::new(),
             })
         })
         .collect::<Result<Vec<_>>>()
@@ -66,7 +77,9 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let pages = scrape_from_html(&document, &chapter).unwrap();
+        let pages =
+            scrape_from_html(&document, &chapter, &Selectors::default())
+                .unwrap();
 
         assert_eq!(pages.len(), 19);
     }
@@ -0,0 +1,65 @@
+use super::selectors::Selectors;
+use crate::{
+    Error,
+    Result,
+    SeriesSummary,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Scrape the series listed in the search results.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    selectors
+        .search_result
+        .filter(html.descendants().elements())
+        .map(|link| {
+            let title = link.text_contents().trim().to_owned();
+            let attributes = link.attributes.borrow();
+            let url = attributes.get("href").ok_or_else(|| {
+                Error::Scraping("series URL not found".to_owned())
+            })?;
+
+            Ok(SeriesSummary {
+                title,
+                url: Url::parse(url).map_err(|err| {
+                    Error::Scraping(format!(
+                        "invalid series URL `{}`: {}",
+                        url, err
+                    ))
+                })?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/webtoonscan.com/search.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let results =
+            scrape_from_html(&document, &Selectors::default()).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].title, "Legend Of The Northern Blade");
+        assert_eq!(
+            results[0].url.as_str(),
+            "https://webtoonscan.com/manhwa/legend-of-the-northern-blade/"
+        );
+        assert_eq!(results[2].title, "Legend of Asura");
+    }
+}
+
+// }}}
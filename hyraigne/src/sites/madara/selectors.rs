@@ -0,0 +1,80 @@
+//! CSS selectors to scrape the Madara websites.
+
+use crate::{
+    sites::SelectorOverrides,
+    SelectorCheck,
+};
+
+/// Name and default value of each selector.
+pub(in crate::sites) const DEFAULTS: [(&str, &str); 6] = [
+    ("series_title", ".post-title h1"),
+    ("series_url", "meta[property=\"og:url\"]"),
+    ("chapter", "li.wp-manga-chapter > a"),
+    (
+        "post_id",
+        "#manga-chapters-holder[data-id], input.rating-post-id",
+    ),
+    ("page_url", ".reading-content img"),
+    ("search_result", ".c-tabs-item__content .post-title a"),
+];
+
+/// CSS selectors to scrape a Madara website.
+pub(super) struct Selectors {
+    /// Select the series title.
+    pub(super) series_title: kuchiki::Selectors,
+    /// Select `<meta property="og:url" content="URL" />`
+    pub(super) series_url: kuchiki::Selectors,
+    /// Select chapter entries in the chapter list.
+    pub(super) chapter: kuchiki::Selectors,
+    /// Select the element holding the series ID, to load the chapter list.
+    pub(super) post_id: kuchiki::Selectors,
+    /// Select image URLs from the chapter page.
+    pub(super) page_url: kuchiki::Selectors,
+    /// Select series links in the search results.
+    pub(super) search_result: kuchiki::Selectors,
+}
+
+impl Selectors {
+    /// Compile the selectors of the website `site`, applying the given
+    /// overrides.
+    pub(super) fn new(site: &str, overrides: &SelectorOverrides) -> Self {
+        let compile = |name| overrides.compile(site, name, &DEFAULTS);
+
+        Self {
+            series_title: compile("series_title"),
+            series_url: compile("series_url"),
+            chapter: compile("chapter"),
+            post_id: compile("post_id"),
+            page_url: compile("page_url"),
+            search_result: compile("search_result"),
+        }
+    }
+
+    /// Run every selector against `html`.
+    pub(super) fn check(&self, html: &kuchiki::NodeRef) -> Vec<SelectorCheck> {
+        vec![
+            SelectorCheck::run("series_title", &self.series_title, html, None),
+            SelectorCheck::run(
+                "series_url",
+                &self.series_url,
+                html,
+                Some("content"),
+            ),
+            SelectorCheck::run("chapter", &self.chapter, html, Some("href")),
+            SelectorCheck::run("post_id", &self.post_id, html, None),
+            SelectorCheck::run("page_url", &self.page_url, html, Some("src")),
+            SelectorCheck::run(
+                "search_result",
+                &self.search_result,
+                html,
+                Some("href"),
+            ),
+        ]
+    }
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self::new("madara", &SelectorOverrides::default())
+    }
+}
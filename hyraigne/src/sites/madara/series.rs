@@ -1,7 +1,4 @@
-use super::selectors::{
-    SERIES_TITLE_SELECTOR,
-    SERIES_URL_SELECTOR,
-};
+use super::selectors::Selectors;
 use crate::{
     types::Pagination,
     Error,
@@ -12,10 +9,13 @@ use kuchiki::traits::*;
 use url::Url;
 
 /// Scrape series info from the given HTML.
-pub(super) fn scrape_from_html(html: &kuchiki::NodeRef) -> Result<Series> {
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Series> {
     Ok(Series {
-        title: title_from_html(&html)?,
-        url: url_from_html(&html)?,
+        title: title_from_html(&html, selectors)?,
+        url: url_from_html(&html, selectors)?,
         // No pagination here, everything is listed on the first page.
         pagination: Pagination::new(0, 0),
     })
@@ -23,8 +23,12 @@ pub(super) fn scrape_from_html(html: &kuchiki::NodeRef) -> Result<Series> {
 
 /// Extract series title from the content of `<div class="post-title">`.
 #[allow(clippy::filter_next)]
-fn title_from_html(html: &kuchiki::NodeRef) -> Result<String> {
-    let raw_title = SERIES_TITLE_SELECTOR
+fn title_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<String> {
+    let raw_title = selectors
+        .series_title
         .filter(html.descendants().elements())
         .next()
         .ok_or_else(|| Error::Scraping("series title not found".to_owned()))?
@@ -39,8 +43,12 @@ fn title_from_html(html: &kuchiki::NodeRef) -> Result<String> {
 
 /// Extract series URL from `<meta property="og:url" content="URL" />`
 #[allow(clippy::filter_next)]
-fn url_from_html(html: &kuchiki::NodeRef) -> Result<Url> {
-    let element = SERIES_URL_SELECTOR
+fn url_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Url> {
+    let element = selectors
+        .series_url
         .filter(html.descendants().elements())
         .next()
         .ok_or_else(|| Error::Scraping("series URL not found".to_owned()))?;
@@ -68,7 +76,8 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let series = scrape_from_html(&document).unwrap();
+        let series =
+            scrape_from_html(&document, &Selectors::default()).unwrap();
 
         assert_eq!(series.title, "Legend Of The Northern Blade");
         assert_eq!(
@@ -0,0 +1,357 @@
+use super::{
+    chapter,
+    config::Config,
+    page,
+    search,
+    selectors::Selectors,
+    series,
+};
+use crate::{
+    sites::{
+        hosts,
+        scraper::{
+            self,
+            ChapterLink,
+            Document,
+            Scraper,
+        },
+    },
+    spiders::Request,
+    Chapter,
+    Filter,
+    LinkKind,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+    SeriesSummary,
+};
+use kuchiki::traits::*;
+use std::sync::Arc;
+use url::Url;
+
+/// A web spider for a Madara website.
+pub(crate) struct Site {
+    config: Arc<Config>,
+    selectors: Selectors,
+}
+
+impl Site {
+    /// Initialize the web spider for the website described by `config`, with
+    /// the given options.
+    pub(crate) fn new(config: Arc<Config>, options: &Options) -> Self {
+        Self {
+            selectors: Selectors::new(config.overrides, &options.selectors),
+            config,
+        }
+    }
+}
+
+impl Scraper for Site {
+    fn search_url(&self, query: &str) -> Result<Url> {
+        let mut url = self.config.base_url();
+        url.query_pairs_mut()
+            .append_pair("s", query)
+            .append_pair("post_type", "wp-manga");
+
+        Ok(url)
+    }
+
+    fn scrape_search(&self, document: &Document) -> Result<Vec<SeriesSummary>> {
+        search::scrape_from_html(document.html()?, &self.selectors)
+    }
+
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        let html = kuchiki::parse_html().one(html);
+
+        Ok(self.selectors.check(&html))
+    }
+
+    fn canonical_url(&self, url: &Url) -> Url {
+        hosts::canonicalize(url, &self.config.host(), &[])
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        if self.parse_chapter_link(url).is_some() {
+            LinkKind::Chapter
+        } else {
+            LinkKind::Series
+        }
+    }
+
+    fn parse_chapter_link(&self, url: &Url) -> Option<ChapterLink> {
+        // Chapters live under their series: `/manga/title/chapter-42/`.
+        let segments = url
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        let (kind, title) = match *segments.as_slice() {
+            [kind, title, _] => (kind, title),
+            _ => return None,
+        };
+        if kind != self.config.quirks.series_path {
+            return None;
+        }
+        let id = chapter::id_from_url(url, &self.config).ok()?;
+
+        let mut series = url.clone();
+        series.set_path(&format!("/{}/{}/", kind, title));
+        series.set_query(None);
+
+        Some(ChapterLink {
+            series,
+            id,
+            volume: None,
+            url: url.clone(),
+        })
+    }
+
+    fn scrape_series(&self, _url: &Url, document: &Document) -> Result<Series> {
+        series::scrape_from_html(document.html()?, &self.selectors)
+    }
+
+    fn scrape_chapters<'a>(
+        &self,
+        _url: &Url,
+        series: &'a Series,
+        _filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        chapter::scrape_from_html(
+            document.html()?,
+            series,
+            &self.config,
+            &self.selectors,
+        )
+    }
+
+    fn chapters_request(
+        &self,
+        _url: &Url,
+        document: &Document,
+    ) -> Result<Option<Request>> {
+        chapter::request_from_html(
+            document.html()?,
+            &self.config,
+            &self.selectors,
+        )
+    }
+
+    fn keep_chapter(&self, chapter: &Chapter<'_>, filter: &Filter) -> bool {
+        scraper::keep_truncated_id(chapter, filter)
+    }
+
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        page::scrape_from_html(document.html()?, chapter, &self.selectors)
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::{
+            engine::Engine,
+            madara::SITES,
+        },
+        testing,
+        Cassette,
+        MemoryTransport,
+        Response,
+        Site as _,
+    };
+    use std::sync::Arc;
+
+    const SERIES_URL: &str =
+        "https://webtoonscan.com/manhwa/legend-of-the-northern-blade/";
+
+    /// Configuration of WebtoonScan.
+    fn webtoonscan() -> Arc<Config> {
+        Arc::new(SITES[0].clone())
+    }
+
+    /// Build the blocking web spider for WebtoonScan.
+    fn spider(options: Options) -> Engine<Site> {
+        Engine::new(Site::new(webtoonscan(), &options), options)
+    }
+
+    #[test]
+    fn test_parse_chapter_link() {
+        let site =
+            Site::new(webtoonscan(), &Options::new(0, 0, std::env::temp_dir()));
+        let series = Url::parse(SERIES_URL).unwrap();
+        let chapter = series.join("42/").unwrap();
+
+        let link = site.parse_chapter_link(&chapter).unwrap();
+
+        assert_eq!(site.classify(&series), LinkKind::Series);
+        assert_eq!(site.classify(&chapter), LinkKind::Chapter);
+        assert_eq!(link.series, series);
+        assert_eq!(link.id, 42.0);
+    }
+
+    #[test]
+    fn test_search_url() {
+        let site =
+            Site::new(webtoonscan(), &Options::new(0, 0, std::env::temp_dir()));
+
+        assert_eq!(
+            site.search_url("tower of god").unwrap().as_str(),
+            "https://webtoonscan.com/?s=tower+of+god&post_type=wp-manga"
+        );
+    }
+
+    #[test]
+    fn test_download() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "webtoonscan.com/series.html");
+        testing::serve(
+            &transport,
+            &format!("{}42/", SERIES_URL),
+            "webtoonscan.com/chapter.html",
+        );
+        let site = spider(testing::options(&transport, "madara-download"));
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        assert_eq!(series.title, "Legend Of The Northern Blade");
+
+        let filter = Filter::new(42..=42, None, Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+        assert_eq!(chapters.len(), 1);
+
+        let pages = site.get_pages(&chapters[0]).unwrap();
+        assert_eq!(pages.len(), 19);
+
+        for page in &pages {
+            transport.insert(&page.main, Response::new(200, "image"));
+        }
+        site.mkdir(&chapters).unwrap();
+        site.download(&pages).unwrap();
+
+        for page in &pages {
+            let path = page.path(&site.output);
+            assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        }
+    }
+
+    #[test]
+    fn test_ajax_chapter_list() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            SERIES_URL,
+            "webtoonscan.com/series-ajax.html",
+        );
+        testing::serve(
+            &transport,
+            "https://webtoonscan.com/wp-admin/admin-ajax.php",
+            "webtoonscan.com/chapters-ajax.html",
+        );
+        let site = spider(testing::options(&transport, "madara-ajax"));
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        let filter = Filter::new(0..=u16::MAX, None, Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+
+        let ids = chapters
+            .iter()
+            .map(|chapter| chapter.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [77.0, 78.0, 79.0]);
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_replay_ajax_chapter_list() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("hyraigne-madara-cassette-{}", std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).expect("clean cassette directory");
+        }
+        let url = Url::parse(SERIES_URL).unwrap();
+        let list_chapters = |site: &Engine<Site>| {
+            let series = site.get_series(&url).unwrap();
+            let filter = Filter::new(0..=u16::MAX, None, Vec::new());
+            site.get_chapters(&series, filter)
+                .unwrap()
+                .iter()
+                .map(|chapter| chapter.id)
+                .collect::<Vec<_>>()
+        };
+
+        // Record…
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            SERIES_URL,
+            "webtoonscan.com/series-ajax.html",
+        );
+        testing::serve(
+            &transport,
+            "https://webtoonscan.com/wp-admin/admin-ajax.php",
+            "webtoonscan.com/chapters-ajax.html",
+        );
+        let cassette = Arc::new(Cassette::create(&dir).unwrap());
+        let site = spider(
+            testing::options(&transport, "madara-record")
+                .with_cassette(cassette),
+        );
+        let recorded = list_chapters(&site);
+
+        // … then replay.
+        let transport = Arc::new(Cassette::replay(&dir).unwrap());
+        let site = spider(testing::options(&transport, "madara-replay"));
+        let replayed = list_chapters(&site);
+
+        assert_eq!(recorded, [77.0, 78.0, 79.0]);
+        assert_eq!(replayed, recorded);
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_ajax_chapter_list() {
+        use crate::{
+            sites::async_engine::AsyncEngine,
+            AsyncSite,
+            AsyncTransport,
+        };
+
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            SERIES_URL,
+            "webtoonscan.com/series-ajax.html",
+        );
+        testing::serve(
+            &transport,
+            "https://webtoonscan.com/wp-admin/admin-ajax.php",
+            "webtoonscan.com/chapters-ajax.html",
+        );
+        let opts = testing::options(&transport, "madara-async")
+            .with_async_transport(
+                Arc::clone(&transport) as Arc<dyn AsyncTransport>
+            );
+        let site = AsyncEngine::new(Site::new(webtoonscan(), &opts), opts);
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        testing::block_on(async {
+            let series = site.get_series(&url).await.unwrap();
+            let filter = Filter::new(78..=79, None, Vec::new());
+            let chapters = site.get_chapters(&series, filter).await.unwrap();
+
+            assert_eq!(chapters.len(), 2);
+        });
+    }
+}
+
+// }}}
@@ -1,12 +1,18 @@
 use super::{
     models::{
         self,
+        ChapterDetail,
+        ChapterFeed,
+        FeedEntry,
         Response,
         SeriesWithChapter,
     },
     API_BASE_URL,
+    NEW_API_BASE_URL,
+    SITE_BASE_URL,
 };
 use crate::{
+    sites::scraper::ChapterLink,
     Chapter,
     Error,
     Filter,
@@ -42,67 +48,188 @@ pub(super) fn extract_from_response<'a>(
         .filter(|chapter| chapter.language == filter.language);
 
     // Then, filter out duplicate (same chapter translated by several teams).
-    let chapters =
-        dedup_chapters(chapters, &filter.preferred_groups, &group_index);
+    let chapters = dedup_chapters(
+        chapters,
+        |chapter| chapter.chapter.clone(),
+        |new, current| {
+            let score = |chapter: &models::Chapter| {
+                let groups = chapter.groups.iter().map(|group_id| {
+                    group_index.get(group_id).map(String::as_str)
+                });
+                get_score(groups, &filter.preferred_groups)
+            };
+
+            let (new_rank, current_rank) = (score(new), score(current));
+
+            // Take the best score, or the most recent if equals.
+            (new_rank < current_rank)
+                || (new_rank == current_rank
+                    && new.timestamp > current.timestamp)
+        },
+    );
 
     // Finally, build the chapter objetcs.
     chapters
         .into_iter()
         .map(|(_, chapter)| {
-            let id = chapter.chapter.parse().map_err(|err| {
-                Error::Scraping(format!(
-                    "invalid chapter ID {}: {}",
-                    chapter.chapter, err
-                ))
-            })?;
-            let endpoint = format!("{}/chapter/{}", API_BASE_URL, chapter.id);
-            let volume = Some(if chapter.volume.is_empty() {
-                "XX".to_owned()
-            } else {
-                chapter.volume
-            });
+            Ok(Chapter {
+                id: parse_id(&chapter.chapter)?,
+                series,
+                volume: Some(volume_name(chapter.volume)),
+                url: endpoint(chapter.id)?,
+            })
+        })
+        .collect()
+}
+
+/// Extract every chapter from the feed served by the new API.
+pub(super) fn extract_from_feed<'a>(
+    response: ChapterFeed,
+    series: &'a Series,
+    filter: &Filter,
+) -> Result<Vec<Chapter<'a>>> {
+    // First, filter by language.
+    let language = language_code(&filter.language);
+    let chapters = response
+        .results
+        .into_iter()
+        .filter(|entry| entry.data.attributes.translated_language == language);
+
+    // Then, filter out duplicate (same chapter translated by several teams).
+    let chapters = dedup_chapters(
+        chapters,
+        |entry| entry.data.attributes.chapter.clone().unwrap_or_default(),
+        |new, current| {
+            let score = |entry: &FeedEntry| {
+                let groups = entry
+                    .relationships
+                    .iter()
+                    .filter(|relationship| {
+                        relationship.kind == "scanlation_group"
+                    })
+                    .map(|relationship| {
+                        relationship
+                            .attributes
+                            .as_ref()
+                            .and_then(|attributes| attributes.name.as_deref())
+                    });
+                get_score(groups, &filter.preferred_groups)
+            };
+            let (new_rank, current_rank) = (score(new), score(current));
+
+            // Take the best score, or the most recent if equals (dates are
+            // all in UTC, so they can be compared as strings).
+            (new_rank < current_rank)
+                || (new_rank == current_rank
+                    && new.data.attributes.publish_at
+                        > current.data.attributes.publish_at)
+        },
+    );
+
+    // Finally, build the chapter objetcs.
+    chapters
+        .into_iter()
+        .map(|(number, entry)| {
+            let attributes = entry.data.attributes;
 
             Ok(Chapter {
-                id,
+                id: parse_id(&number)?,
                 series,
-                volume,
-                url: Url::parse(&endpoint).map_err(|err| {
-                    Error::Scraping(format!(
-                        "invalid chapter endpoint {}: {}",
-                        endpoint, err
-                    ))
-                })?,
+                volume: Some(volume_name(
+                    attributes.volume.unwrap_or_default(),
+                )),
+                url: entry_endpoint(&entry.data.id)?,
             })
         })
         .collect()
 }
 
-/// Filter our duplicated chapters based on a computed score.
-fn dedup_chapters(
-    chapters: impl Iterator<Item = models::Chapter>,
-    preferred_groups: &[String],
-    group_index: &HashMap<u32, String>,
-) -> BTreeMap<String, models::Chapter> {
+/// Extract the chapter from the chapter details served by the API.
+pub(super) fn extract_link_from_response(
+    response: Response<ChapterDetail>,
+) -> Result<ChapterLink> {
+    let chapter = response.data;
+    let series = format!("{}/title/{}", SITE_BASE_URL, chapter.manga_id);
+
+    Ok(ChapterLink {
+        series: Url::parse(&series).map_err(|err| {
+            Error::Scraping(format!("invalid series URL {}: {}", series, err))
+        })?,
+        id: parse_id(&chapter.chapter)?,
+        volume: Some(volume_name(chapter.volume)),
+        url: endpoint(chapter.id)?,
+    })
+}
+
+/// API endpoint of the chapter `id`.
+pub(super) fn endpoint(id: u64) -> Result<Url> {
+    let endpoint = format!("{}/chapter/{}", API_BASE_URL, id);
+
+    Url::parse(&endpoint).map_err(|err| {
+        Error::Scraping(format!(
+            "invalid chapter endpoint {}: {}",
+            endpoint, err
+        ))
+    })
+}
+
+/// Endpoint of the chapter `id` on the new API.
+pub(super) fn entry_endpoint(id: &str) -> Result<Url> {
+    let endpoint = format!("{}/chapter/{}", NEW_API_BASE_URL, id);
+
+    Url::parse(&endpoint).map_err(|err| {
+        Error::Scraping(format!(
+            "invalid chapter endpoint {}: {}",
+            endpoint, err
+        ))
+    })
+}
+
+/// Convert a language code of the legacy API into its new API equivalent.
+pub(super) fn language_code(code: &str) -> &str {
+    match code {
+        "gb" => "en",
+        "br" => "pt-br",
+        "mx" => "es-la",
+        _ => code,
+    }
+}
+
+/// Parse the chapter number into a chapter ID.
+fn parse_id(chapter: &str) -> Result<f64> {
+    chapter.parse().map_err(|err| {
+        Error::Scraping(format!("invalid chapter ID {}: {}", chapter, err))
+    })
+}
+
+/// Name of the volume, `XX` when unknown.
+fn volume_name(volume: String) -> String {
+    if volume.is_empty() {
+        "XX".to_owned()
+    } else {
+        volume
+    }
+}
+
+/// Filter our duplicated chapters, keeping the best version of each.
+///
+/// Chapters are identified by `number`, and `is_better` tells if its first
+/// argument should replace the second one.
+fn dedup_chapters<T>(
+    chapters: impl Iterator<Item = T>,
+    number: impl Fn(&T) -> String,
+    is_better: impl Fn(&T, &T) -> bool,
+) -> BTreeMap<String, T> {
     let mut result = BTreeMap::new();
 
     for chapter in chapters {
-        match result.entry(chapter.chapter.clone()) {
+        match result.entry(number(&chapter)) {
             Entry::Vacant(slot) => {
                 slot.insert(chapter);
             },
             // Choose between two versions of the same chapter.
             Entry::Occupied(mut slot) => {
-                let current = slot.get();
-                let current_rank =
-                    get_score(current, preferred_groups, group_index);
-                let new_rank =
-                    get_score(&chapter, preferred_groups, group_index);
-
-                // Take the best score, or the most recent if equals.
-                if (new_rank < current_rank)
-                    || (new_rank == current_rank
-                        && chapter.timestamp > current.timestamp)
-                {
+                if is_better(&chapter, slot.get()) {
                     slot.insert(chapter);
                 }
             },
@@ -112,32 +239,24 @@ fn dedup_chapters(
     result
 }
 
-/// Compute a score for the chapter.
+/// Compute a score for a chapter scanlated by `groups`.
 ///
-/// The score is based on the team that scanlated the chapter: teams that are in
-/// the list of preferred groups get a better score.
+/// Teams that are in the list of preferred groups get a better score, unknown
+/// teams (`None`) get the worst one.
 ///
 /// Lower is better.
-fn get_score(
-    chapter: &models::Chapter,
+fn get_score<'a>(
+    groups: impl Iterator<Item = Option<&'a str>>,
     preferred_groups: &[String],
-    group_index: &HashMap<u32, String>,
 ) -> usize {
     let default = usize::MAX;
 
-    chapter
-        .groups
-        .iter()
-        .map(|group_id| {
-            match group_index.get(group_id) {
-                Some(name) => {
-                    preferred_groups
-                        .iter()
-                        .position(|group| group == name)
-                        .unwrap_or(default)
-                },
-                None => default,
-            }
+    groups
+        .map(|name| {
+            name.and_then(|name| {
+                preferred_groups.iter().position(|group| group == name)
+            })
+            .unwrap_or(default)
         })
         .min()
         .unwrap_or(default)
@@ -173,6 +292,63 @@ mod tests {
 
         assert_eq!(chapters.len(), 673);
     }
+
+    #[test]
+    fn test_feed_scraping() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangadex.org/feed.json");
+        let json = std::fs::read_to_string(&path).expect("test data");
+        let scrape = |groups: Vec<String>| {
+            let filter =
+                Filter::new(0..=u16::MAX, Some("gb".to_owned()), groups);
+            let response = serde_json::from_str(&json).expect("invalid JSON");
+            extract_from_feed(response, &series, &filter)
+                .unwrap()
+                .into_iter()
+                .map(|chapter| (chapter.id, chapter.url.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let endpoint = |id| {
+            format!(
+                "https://api.mangadex.org/chapter/7e3c9a1b-2d4f-4a6b-8c0d-{:012}",
+                id
+            )
+        };
+
+        // Most recent version wins by default…
+        assert_eq!(
+            scrape(Vec::new()),
+            vec![(1.0, endpoint(2)), (2.0, endpoint(3)), (2.5, endpoint(5))]
+        );
+        // … unless a preferred group translated the chapter.
+        assert_eq!(
+            scrape(vec!["Alpha".to_owned()]),
+            vec![(1.0, endpoint(1)), (2.0, endpoint(3)), (2.5, endpoint(5))]
+        );
+    }
+
+    #[test]
+    fn test_link_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangadex.org/chapter.json");
+        let json = std::fs::read_to_string(&path).expect("test data");
+        let response = serde_json::from_str(&json).expect("invalid JSON");
+
+        let link = extract_link_from_response(response).unwrap();
+
+        assert_eq!(link.series.as_str(), "https://mangadex.org/title/642");
+        assert_eq!(link.id, 1.0);
+        assert_eq!(link.volume.as_deref(), Some("1"));
+        assert_eq!(
+            link.url.as_str(),
+            "https://api.mangadex.org/v2/chapter/482684"
+        );
+    }
 }
 
 // }}}
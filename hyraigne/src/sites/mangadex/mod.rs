@@ -1,10 +1,33 @@
 mod chapter;
 mod models;
 mod page;
+mod search;
 mod series;
 mod site;
 
 /// Mangadex API address.
 pub(super) const API_BASE_URL: &str = "https://api.mangadex.org/v2";
 
+/// Address of the new Mangadex API (v5), where series and chapters are
+/// identified by UUID.
+pub(super) const NEW_API_BASE_URL: &str = "https://api.mangadex.org";
+
+/// Mangadex endpoint to search and list series.
+///
+/// The legacy API (v2) doesn't provide such feature, so we use the new one
+/// here.
+pub(super) const MANGA_LIST_ENDPOINT: &str = "https://api.mangadex.org/manga";
+
+/// Number of series per page when browsing a listing.
+pub(super) const LISTING_PAGE_SIZE: u32 = 20;
+
+/// Maximum number of chapters returned by the new API in one response.
+pub(super) const FEED_PAGE_SIZE: u32 = 500;
+
+/// Server hosting the pages of the chapters served by the new API.
+pub(super) const UPLOADS_BASE_URL: &str = "https://uploads.mangadex.org/data/";
+
+/// Mangadex website address.
+pub(super) const SITE_BASE_URL: &str = "https://mangadex.org";
+
 pub(crate) use site::Site;
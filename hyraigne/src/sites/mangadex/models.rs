@@ -4,20 +4,18 @@
 //! download chapters.
 
 use serde::Deserialize;
+use std::collections::HashMap;
 use url::Url;
 
 /// Response fron the Mangadex API.
 #[derive(Debug, Deserialize)]
 pub(super) struct Response<T> {
-    pub(super) code: u16,
-    pub(super) status: String,
     pub(super) data: T,
 }
 
 /// Series info with chapters included.
 #[derive(Debug, Deserialize)]
 pub(super) struct SeriesWithChapter {
-    pub(super) manga: Series,
     pub(super) chapters: Vec<Chapter>,
     pub(super) groups: Vec<Group>,
 }
@@ -34,7 +32,6 @@ pub(super) struct Series {
 #[serde(rename_all = "camelCase")]
 pub(super) struct Chapter {
     pub(super) id: u64,
-    pub(super) manga_title: String,
     pub(super) volume: String,
     pub(super) chapter: String,
     pub(super) language: String,
@@ -55,10 +52,85 @@ pub(super) struct Group {
 pub(super) struct ChapterDetail {
     pub(super) id: u64,
     pub(super) hash: String,
+    pub(super) manga_id: u64,
     pub(super) volume: String,
     pub(super) chapter: String,
-    pub(super) language: String,
     pub(super) pages: Vec<String>,
     pub(super) server: Url,
     pub(super) server_fallback: Url,
 }
+
+/// Search results.
+#[derive(Debug, Deserialize)]
+pub(super) struct SearchResults {
+    pub(super) results: Vec<SearchResult>,
+}
+
+/// A single search result.
+#[derive(Debug, Deserialize)]
+pub(super) struct SearchResult {
+    pub(super) data: Manga,
+}
+
+/// Series info, as returned by the new API.
+#[derive(Debug, Deserialize)]
+pub(super) struct Manga {
+    pub(super) id: String,
+    pub(super) attributes: MangaAttributes,
+}
+
+/// Series attributes, as returned by the new API.
+#[derive(Debug, Deserialize)]
+pub(super) struct MangaAttributes {
+    /// Series title, indexed by language code.
+    pub(super) title: HashMap<String, String>,
+}
+
+/// Chapters of a series, as returned by the new API.
+#[derive(Debug, Deserialize)]
+pub(super) struct ChapterFeed {
+    pub(super) results: Vec<FeedEntry>,
+}
+
+/// A single chapter of the feed, with its related entities.
+#[derive(Debug, Deserialize)]
+pub(super) struct FeedEntry {
+    pub(super) data: ChapterEntry,
+    pub(super) relationships: Vec<Relationship>,
+}
+
+/// Chapter info, as returned by the new API.
+#[derive(Debug, Deserialize)]
+pub(super) struct ChapterEntry {
+    pub(super) id: String,
+    pub(super) attributes: ChapterAttributes,
+}
+
+/// Chapter attributes, as returned by the new API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ChapterAttributes {
+    pub(super) volume: Option<String>,
+    pub(super) chapter: Option<String>,
+    pub(super) translated_language: String,
+    pub(super) hash: String,
+    /// Page file names.
+    pub(super) data: Vec<String>,
+    /// Publication date, in RFC 3339 format.
+    pub(super) publish_at: String,
+}
+
+/// Entity related to a chapter (series, scantrad group, …).
+#[derive(Debug, Deserialize)]
+pub(super) struct Relationship {
+    #[serde(rename = "type")]
+    pub(super) kind: String,
+    /// Only present when the entity is explicitly included.
+    pub(super) attributes: Option<RelationshipAttributes>,
+}
+
+/// Attributes of a related entity.
+#[derive(Debug, Deserialize)]
+pub(super) struct RelationshipAttributes {
+    pub(super) name: Option<String>,
+}
@@ -1,6 +1,10 @@
-use super::models::{
-    ChapterDetail,
-    Response,
+use super::{
+    models::{
+        ChapterDetail,
+        ChapterEntry,
+        Response,
+    },
+    UPLOADS_BASE_URL,
 };
 use crate::{
     Chapter,
@@ -30,7 +34,38 @@ pub(super) fn extract_from_response<'a>(
                 id: (idx + 1) as u16,
                 chapter,
                 main: urljoin(server_url, &path)?,
-                fallback: Some(urljoin(fallback_url, &path)?),
+                fallbacks: vec![urljoin(fallback_url, &path)?],
+            })
+        })
+        .collect()
+}
+
+/// Extract page links from the new API response.
+pub(super) fn extract_from_entry<'a>(
+    response: &Response<ChapterEntry>,
+    chapter: &'a Chapter<'_>,
+) -> Result<Vec<Page<'a>>> {
+    let attributes = &response.data.attributes;
+    let server_url = Url::parse(UPLOADS_BASE_URL).map_err(|err| {
+        Error::Scraping(format!(
+            "invalid page server {}: {}",
+            UPLOADS_BASE_URL, err
+        ))
+    })?;
+
+    attributes
+        .data
+        .iter()
+        .enumerate()
+        .map(|(idx, page)| {
+            let path = format!("{}/{}", attributes.hash, page);
+
+            #[allow(clippy::cast_possible_truncation)] // Page number is small.
+            Ok(Page {
+                id: (idx + 1) as u16,
+                chapter,
+                main: urljoin(&server_url, &path)?,
+                fallbacks: Vec::new(),
             })
         })
         .collect()
@@ -81,6 +116,34 @@ mod tests {
 
         assert_eq!(pages.len(), 62);
     }
+
+    #[test]
+    fn test_scraping_entry() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let chapter = Chapter {
+            id: 1.0,
+            series: &series,
+            volume: None,
+            url: Url::parse("http://example.com/1/").unwrap(),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangadex.org/chapter_entry.json");
+        let json = std::fs::read_to_string(&path).expect("test data");
+        let response = serde_json::from_str(&json).expect("invalid JSON");
+
+        let pages = extract_from_entry(&response, &chapter).unwrap();
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(
+            pages[0].main.as_str(),
+            "https://uploads.mangadex.org/data/8a4f2c0b9e1d3f5a7c6b8d0e2f4a6c8e/x1-3f2a.png"
+        );
+        assert!(pages[0].fallbacks.is_empty());
+    }
 }
 
 // }}}
@@ -0,0 +1,77 @@
+use super::{
+    models::SearchResults,
+    series,
+    SITE_BASE_URL,
+};
+use crate::{
+    Error,
+    Result,
+    SeriesSummary,
+};
+use url::Url;
+
+/// Extract the series listed in the search results.
+pub(super) fn extract_from_response(
+    response: SearchResults,
+) -> Result<Vec<SeriesSummary>> {
+    response
+        .results
+        .into_iter()
+        .map(|result| {
+            let id = result.data.id;
+            let title = series::pick_title(&id, result.data.attributes.title)?;
+            let url = format!("{}/title/{}", SITE_BASE_URL, id);
+
+            Ok(SeriesSummary {
+                title,
+                url: Url::parse(&url).map_err(|err| {
+                    Error::Scraping(format!(
+                        "invalid series URL {}: {}",
+                        url, err
+                    ))
+                })?,
+            })
+        })
+        .collect()
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangadex.org/search.json");
+        let json = std::fs::read_to_string(&path).expect("test data");
+        let response = serde_json::from_str(&json).expect("invalid JSON");
+
+        let results = extract_from_response(response).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].title, "Kingdom");
+        assert_eq!(
+            results[0].url.as_str(),
+            "https://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f"
+        );
+        assert_eq!(results[2].title, "Oukoku");
+    }
+
+    #[test]
+    fn test_scraping_latest() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangadex.org/latest.json");
+        let json = std::fs::read_to_string(&path).expect("test data");
+        let response = serde_json::from_str(&json).expect("invalid JSON");
+
+        let results = extract_from_response(response).unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1].title, "Solo Leveling");
+    }
+}
+
+// }}}
@@ -1,15 +1,18 @@
 use super::{
     models::{
+        Manga,
         Response,
         Series,
     },
     API_BASE_URL,
+    NEW_API_BASE_URL,
 };
 use crate::{
     types::Pagination,
     Error,
     Result,
 };
+use std::collections::HashMap;
 use url::Url;
 
 /// Extract series metadata from the API response.
@@ -30,6 +33,44 @@ pub(super) fn extract_from_response(
     })
 }
 
+/// Extract series metadata from the new API response.
+pub(super) fn extract_from_manga(
+    response: Response<Manga>,
+) -> Result<crate::Series> {
+    let endpoint = format!("{}/manga/{}", NEW_API_BASE_URL, response.data.id);
+
+    Ok(crate::Series {
+        title: pick_title(&response.data.id, response.data.attributes.title)?,
+        url: Url::parse(&endpoint).map_err(|err| {
+            Error::Scraping(format!(
+                "invalid series endpoint {}: {}",
+                endpoint, err
+            ))
+        })?,
+        pagination: Pagination::new(0, 0),
+    })
+}
+
+/// Select the title of the series `id` among its translations.
+pub(super) fn pick_title(
+    id: &str,
+    mut titles: HashMap<String, String>,
+) -> Result<String> {
+    // Prefer the english title, if any, otherwise pick the first language
+    // code in alphabetical order: `HashMap` iteration order isn't stable.
+    titles
+        .remove("en")
+        .or_else(|| {
+            titles
+                .into_iter()
+                .min_by(|&(ref lhs, _), &(ref rhs, _)| lhs.cmp(rhs))
+                .map(|(_, title)| title)
+        })
+        .ok_or_else(|| {
+            Error::Scraping(format!("title not found for series {}", id))
+        })
+}
+
 // Tests {{{
 
 #[cfg(test)]
@@ -54,6 +95,44 @@ mod tests {
         assert_eq!(series.pagination.chapter_count, 0);
         assert_eq!(series.pagination.page_size, 0);
     }
+
+    #[test]
+    fn test_scraping_manga() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangadex.org/manga.json");
+        let json = std::fs::read_to_string(&path).expect("test data");
+        let response = serde_json::from_str(&json).expect("invalid JSON");
+
+        let series = extract_from_manga(response).unwrap();
+
+        assert_eq!(series.title, "Kingdom");
+        assert_eq!(
+            series.url.as_str(),
+            "https://api.mangadex.org/manga/a1c7c817-4e59-43b7-9365-09675a149a6f"
+        );
+    }
+
+    #[test]
+    fn test_pick_title() {
+        let titles = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|&(lang, title)| (lang.to_owned(), title.to_owned()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert_eq!(
+            pick_title("42", titles(&[("ja", "Oukoku"), ("en", "Kingdom")]))
+                .unwrap(),
+            "Kingdom"
+        );
+        assert_eq!(
+            pick_title("42", titles(&[("ja", "Oukoku"), ("fr", "Royaume")]))
+                .unwrap(),
+            "Royaume"
+        );
+        assert!(pick_title("42", HashMap::new()).is_err());
+    }
 }
 
 // }}}
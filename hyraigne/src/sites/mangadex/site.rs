@@ -3,190 +3,236 @@ use super::{
     models::{
         self,
         ChapterDetail,
+        ChapterEntry,
+        ChapterFeed,
+        Manga,
         Response,
+        SearchResults,
         SeriesWithChapter,
     },
     page,
+    search,
     series,
     API_BASE_URL,
+    FEED_PAGE_SIZE,
+    LISTING_PAGE_SIZE,
+    MANGA_LIST_ENDPOINT,
+    NEW_API_BASE_URL,
 };
 use crate::{
-    spiders::HttpClient,
+    sites::{
+        hosts,
+        scraper::{
+            ChapterLink,
+            Document,
+            Format,
+            Scraper,
+        },
+    },
     Chapter,
     Error,
     Filter,
-    Options,
+    LinkKind,
+    Listing,
     Page,
     Result,
     Series,
+    SeriesSummary,
 };
 use once_cell::unsync::Lazy;
 use regex::Regex;
-use std::path::PathBuf;
 use url::Url;
 
 /// A web spider for `https://mangadex.org`.
-pub(crate) struct Site {
-    spider: HttpClient,
-    output: PathBuf,
-}
+pub(crate) struct Site;
 
-impl Site {
-    /// Initialize the web spider with the given options.
-    pub(crate) fn new(options: Options) -> Self {
-        Self {
-            spider: HttpClient::new(options.delay, options.retry, None),
-            output: options.output,
-        }
+impl Scraper for Site {
+    fn format(&self) -> Format {
+        Format::Json
+    }
+
+    #[allow(clippy::expect_used)] // Hardcoded values should be valid…
+    fn search_url(&self, query: &str) -> Result<Url> {
+        let mut url = Url::parse(MANGA_LIST_ENDPOINT).expect("valid URL");
+        url.query_pairs_mut().append_pair("title", query);
+
+        Ok(url)
     }
-}
 
-impl crate::Site for Site {
-    fn get_series(&self, url: &Url) -> Result<Series> {
-        let endpoint = endpoint_from_url(&url)?;
+    fn scrape_search(&self, document: &Document) -> Result<Vec<SeriesSummary>> {
+        search::extract_from_response(document.json::<SearchResults>()?)
+    }
 
-        log::info!("scraping series info from {}…", endpoint.as_str());
+    #[allow(clippy::expect_used)] // Hardcoded values should be valid…
+    fn listing_url(&self, listing: Listing, page: u16) -> Result<Option<Url>> {
+        // Only the recently updated series can be listed through the API.
+        if listing == Listing::Popular {
+            return Err(Error::Unsupported("browsing popular series"));
+        }
 
-        let response: Response<models::Series> =
-            self.spider.get_json(&endpoint)?;
-        let series =
-            series::extract_from_response(response).map_err(|err| {
-                Error::Scraping(format!(
-                    "failed to scrape serie from {}: {}",
-                    endpoint.as_str(),
-                    err
-                ))
-            })?;
+        let offset = u32::from(page.saturating_sub(1)) * LISTING_PAGE_SIZE;
+        let mut url = Url::parse(MANGA_LIST_ENDPOINT).expect("valid URL");
+        url.query_pairs_mut()
+            .append_pair("order[updatedAt]", "desc")
+            .append_pair("limit", &LISTING_PAGE_SIZE.to_string())
+            .append_pair("offset", &offset.to_string());
 
-        Ok(series)
+        Ok(Some(url))
     }
 
-    fn get_chapters<'a>(
+    fn scrape_listing(
         &self,
-        series: &'a Series,
-        filter: Filter,
-    ) -> Result<Vec<Chapter<'a>>> {
-        log::info!("scraping chapter links for series {}…", series.title);
+        _listing: Listing,
+        document: &Document,
+    ) -> Result<Vec<SeriesSummary>> {
+        search::extract_from_response(document.json::<SearchResults>()?)
+    }
 
-        let mut url = series.url.clone();
-        url.set_query(Some("include=chapters"));
-
-        let response: Response<SeriesWithChapter> =
-            self.spider.get_json(&url)?;
-        let chapters =
-            chapter::extract_from_response(response, &series, &filter)
-                .map_err(|err| {
-                    Error::Scraping(format!(
-                        "failed to scrape chapters from {}: {}",
-                        url.as_str(),
-                        err
-                    ))
-                })?;
-        log::debug!("found {} chapters", chapters.len());
-
-        let start = f64::from(*filter.range.start());
-        let end = f64::from(*filter.range.end());
-        let range = start..=end;
-        // Trim the chapters list to keep only the requested chapters.
-        let mut chapters = chapters
-            .into_iter()
-            // Chapter IDs are always positive and under u16::MAX.
-            .filter(|chapter| range.contains(&chapter.id))
-            .collect::<Vec<_>>();
-
-        #[allow(clippy::expect_used)] // Shouldn't have NaN & friends here…
-        chapters
-            .sort_by(|a, b| a.partial_cmp(b).expect("abnormal float as ID"));
-        log::debug!("selected {} chapters", chapters.len());
-
-        Ok(chapters)
-    }
-
-    fn get_pages<'a>(&self, chapter: &'a Chapter<'_>) -> Result<Vec<Page<'a>>> {
-        log::info!("scraping page links for chapter {}…", chapter.id);
-
-        let response: Response<ChapterDetail> =
-            self.spider.get_json(&chapter.url)?;
-        let pages =
-            page::extract_from_response(&response, chapter).map_err(|err| {
-                Error::Scraping(format!(
-                    "failed to pages from {}: {}",
-                    chapter.url.as_str(),
-                    err
-                ))
-            })?;
-
-        log::debug!("found {} pages in chapter {}", pages.len(), chapter.id);
-
-        Ok(pages)
-    }
-
-    fn mkdir(&self, chapters: &[Chapter<'_>]) -> Result<()> {
-        for chapter in chapters {
-            let path = chapter.path(&self.output);
-            crate::fs::mkdir_p(&path)?;
+    fn canonical_url(&self, url: &Url) -> Url {
+        hosts::canonicalize(url, "mangadex.org", &[])
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        match chapter_from_url(url) {
+            Some((_, true)) => LinkKind::Page,
+            Some((_, false)) => LinkKind::Chapter,
+            None => LinkKind::Series,
         }
+    }
+
+    fn chapter_url(&self, url: &Url) -> Result<Url> {
+        let (id, _) = chapter_from_url(url).ok_or_else(|| {
+            Error::UnsupportedUrl {
+                url: url.to_string(),
+            }
+        })?;
 
-        Ok(())
+        chapter::endpoint(id)
     }
 
-    fn download(&self, pages: &[Page<'_>]) -> Result<()> {
-        log::info!(
-            "downloading {} pages for chapter {}…",
-            pages.len(),
-            pages[0].chapter.id,
-        );
+    fn scrape_chapter(
+        &self,
+        _url: &Url,
+        document: &Document,
+    ) -> Result<ChapterLink> {
+        chapter::extract_link_from_response(
+            document.json::<Response<ChapterDetail>>()?,
+        )
+    }
+
+    fn series_url(&self, url: &Url) -> Result<Url> {
+        endpoint_from_url(url)
+    }
+
+    fn scrape_series(&self, url: &Url, document: &Document) -> Result<Series> {
+        if is_legacy(url) {
+            series::extract_from_response(
+                document.json::<Response<models::Series>>()?,
+            )
+        } else {
+            series::extract_from_manga(document.json::<Response<Manga>>()?)
+        }
+    }
 
-        let mut bytes: Vec<u8> = Vec::new();
+    fn chapters_url(
+        &self,
+        series: &Series,
+        filter: &Filter,
+        index: u16,
+    ) -> Option<Url> {
+        if index > 0 {
+            return None;
+        }
 
-        for page in pages.iter() {
-            // Compute the image path.
-            let path = page.path(&self.output);
+        let mut url = series.url.clone();
+        if is_legacy(&series.url) {
+            url.set_query(Some("include=chapters"));
+        } else {
+            url.path_segments_mut().ok()?.push("feed");
+            url.query_pairs_mut()
+                .append_pair("limit", &FEED_PAGE_SIZE.to_string())
+                .append_pair(
+                    "translatedLanguage[]",
+                    chapter::language_code(&filter.language),
+                )
+                .append_pair("includes[]", "scanlation_group");
+        }
 
-            // Skip it if it has already been downloaded.
-            if path.exists() {
-                log::debug!("{} already exists, skip", path.display());
-                continue;
-            }
+        Some(url)
+    }
 
-            log::info!("downloading {}…", path.display());
-            self.spider
-                .get_image(&page.main, &page.chapter.url, &mut bytes)
-                .or_else(|err| {
-                    page.fallback.as_ref().map_or(Err(err), |fallback_url| {
-                        self.spider.get_image(
-                            fallback_url,
-                            &page.chapter.url,
-                            &mut bytes,
-                        )
-                    })
-                })?;
-
-            crate::fs::atomic_save(&path, &bytes)?;
-
-            bytes.clear();
+    fn scrape_chapters<'a>(
+        &self,
+        url: &Url,
+        series: &'a Series,
+        filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        if is_legacy(url) {
+            chapter::extract_from_response(
+                document.json::<Response<SeriesWithChapter>>()?,
+                series,
+                filter,
+            )
+        } else {
+            chapter::extract_from_feed(
+                document.json::<ChapterFeed>()?,
+                series,
+                filter,
+            )
         }
+    }
 
-        Ok(())
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        if is_legacy(&chapter.url) {
+            page::extract_from_response(
+                &document.json::<Response<ChapterDetail>>()?,
+                chapter,
+            )
+        } else {
+            page::extract_from_entry(
+                &document.json::<Response<ChapterEntry>>()?,
+                chapter,
+            )
+        }
     }
 }
 
+// Tell if `url` is an endpoint of the legacy API.
+fn is_legacy(url: &Url) -> bool {
+    url.as_str().starts_with(API_BASE_URL)
+}
+
 // Convert a series URL into the corresponding API endpoint.
+//
+// Series still known by their legacy numeric ID are served by the legacy API,
+// those identified by UUID (e.g. from search results) by the new one.
 #[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
 fn endpoint_from_url(url: &Url) -> Result<Url> {
     let extract_id = Lazy::new(|| {
-        Regex::new(r#"^/title/(?P<id>\d+)"#).expect("invalid series ID regexp")
+        Regex::new(
+            r#"^/title/(?:(?P<id>\d+)|(?P<uuid>[[:xdigit:]]{8}(?:-[[:xdigit:]]{4}){3}-[[:xdigit:]]{12}))(?:/|$)"#,
+        )
+        .expect("invalid series ID regexp")
     });
 
-    let id = extract_id
-        .captures(url.path())
-        .ok_or_else(|| {
-            Error::Scraping(format!("series ID not found in {}", url.as_str()))
-        })?
-        .name("id")
-        .expect("invalid capture group for series ID");
-
-    let endpoint = format!("{}/manga/{}", API_BASE_URL, id.as_str());
+    let captures = extract_id.captures(url.path()).ok_or_else(|| {
+        Error::UnsupportedUrl {
+            url: url.to_string(),
+        }
+    })?;
+    let endpoint = if let Some(id) = captures.name("id") {
+        format!("{}/manga/{}", API_BASE_URL, id.as_str())
+    } else {
+        let uuid = captures
+            .name("uuid")
+            .expect("invalid capture group for series ID");
+        format!("{}/manga/{}", NEW_API_BASE_URL, uuid.as_str())
+    };
 
     Url::parse(&endpoint).map_err(|err| {
         Error::Scraping(format!(
@@ -196,11 +242,39 @@ fn endpoint_from_url(url: &Url) -> Result<Url> {
     })
 }
 
+// Extract the chapter ID from a chapter (or page) URL.
+//
+// Also tell if the URL points to a specific page of the chapter.
+#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
+fn chapter_from_url(url: &Url) -> Option<(u64, bool)> {
+    let extract_id = Lazy::new(|| {
+        Regex::new(r#"^/chapter/(?P<id>\d+)(?:/(?P<page>\d+))?/?$"#)
+            .expect("invalid chapter ID regexp")
+    });
+
+    let captures = extract_id.captures(url.path())?;
+    let id = captures
+        .name("id")
+        .expect("invalid capture group for chapter ID")
+        .as_str()
+        .parse()
+        .ok()?;
+
+    Some((id, captures.name("page").is_some()))
+}
+
 // Tests {{{
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        sites::engine::Engine,
+        testing,
+        MemoryTransport,
+        Site as _,
+    };
+    use std::sync::Arc;
 
     #[test]
     fn test_endpoint_from_url() {
@@ -210,6 +284,152 @@ mod tests {
         let endpoint = endpoint_from_url(&url).unwrap();
 
         assert_eq!(endpoint.as_str(), "https://api.mangadex.org/v2/manga/642");
+
+        let url = Url::parse(
+            "https://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f",
+        )
+        .unwrap();
+
+        let endpoint = endpoint_from_url(&url).unwrap();
+
+        assert_eq!(
+            endpoint.as_str(),
+            "https://api.mangadex.org/manga/a1c7c817-4e59-43b7-9365-09675a149a6f"
+        );
+    }
+
+    #[test]
+    fn test_browse_then_get_series() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/manga?order%5BupdatedAt%5D=desc&limit=20&offset=0",
+            "mangadex.org/latest.json",
+        );
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/manga/a1c7c817-4e59-43b7-9365-09675a149a6f",
+            "mangadex.org/manga.json",
+        );
+        let site = Engine::new(Site, testing::options(&transport, "md-browse"));
+
+        let results = site.browse(Listing::Latest, 1).unwrap();
+        let series = site.get_series(&results[0].url).unwrap();
+
+        assert_eq!(series.title, results[0].title);
+        assert_eq!(
+            series.url.as_str(),
+            "https://api.mangadex.org/manga/a1c7c817-4e59-43b7-9365-09675a149a6f"
+        );
+    }
+
+    #[test]
+    fn test_search_then_download() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/manga?title=kingdom",
+            "mangadex.org/search.json",
+        );
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/manga/a1c7c817-4e59-43b7-9365-09675a149a6f",
+            "mangadex.org/manga.json",
+        );
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/manga/a1c7c817-4e59-43b7-9365-09675a149a6f/feed?limit=500&translatedLanguage%5B%5D=en&includes%5B%5D=scanlation_group",
+            "mangadex.org/feed.json",
+        );
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/chapter/7e3c9a1b-2d4f-4a6b-8c0d-000000000002",
+            "mangadex.org/chapter_entry.json",
+        );
+        let site = Engine::new(Site, testing::options(&transport, "md-search"));
+
+        let results = site.search("kingdom").unwrap();
+        let series = site.get_series(&results[0].url).unwrap();
+        let filter = Filter::new(1..=1, Some("gb".to_owned()), Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+        let pages = site.get_pages(&chapters[0]).unwrap();
+
+        assert_eq!(series.title, "Kingdom");
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(pages.len(), 3);
+    }
+
+    #[test]
+    fn test_classify() {
+        let classify = |url| Site.classify(&Url::parse(url).unwrap());
+
+        assert_eq!(
+            classify("https://mangadex.org/title/642/kingdom/"),
+            LinkKind::Series
+        );
+        assert_eq!(
+            classify("https://mangadex.org/chapter/482684"),
+            LinkKind::Chapter
+        );
+        assert_eq!(
+            classify("https://mangadex.org/chapter/482684/3"),
+            LinkKind::Page
+        );
+    }
+
+    #[test]
+    fn test_get_chapter_from_page_link() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/v2/chapter/482684",
+            "mangadex.org/chapter.json",
+        );
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/v2/manga/642",
+            "mangadex.org/series.json",
+        );
+        let site =
+            Engine::new(Site, testing::options(&transport, "md-chapter-link"));
+        let url = Url::parse("https://mangadex.org/chapter/482684/3").unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        let chapter = site.get_chapter(&series, &url).unwrap();
+
+        assert_eq!(series.title, "Kingdom");
+        assert_eq!(chapter.id, 1.0);
+        assert_eq!(
+            chapter.url.as_str(),
+            "https://api.mangadex.org/v2/chapter/482684"
+        );
+    }
+
+    #[test]
+    fn test_get_series_and_chapters() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/v2/manga/642",
+            "mangadex.org/series.json",
+        );
+        testing::serve(
+            &transport,
+            "https://api.mangadex.org/v2/manga/642?include=chapters",
+            "mangadex.org/series_details.json",
+        );
+        let site =
+            Engine::new(Site, testing::options(&transport, "md-chapters"));
+        let url =
+            Url::parse("https://mangadex.org/title/642/kingdom/").unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        let filter = Filter::new(1..=10, Some("gb".to_owned()), Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+
+        assert_eq!(series.title, "Kingdom");
+        assert_eq!(chapters.first().map(|chapter| chapter.id), Some(1.0));
+        assert_eq!(chapters.last().map(|chapter| chapter.id), Some(10.0));
     }
 }
 
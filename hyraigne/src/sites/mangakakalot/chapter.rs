@@ -1,22 +1,32 @@
-use super::selectors::CHAPTER_SELECTOR;
+use super::selectors::Selectors;
 use crate::{
+    sites::scraper::ChapterLink,
     Chapter,
     Error,
     Result,
     Series,
 };
 use kuchiki::traits::*;
-use once_cell::unsync::Lazy;
+use once_cell::sync::Lazy;
 use regex::Regex;
 use url::Url;
 
+/// Extract chapter ID and volume (if any) from chapter's title.
+#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
+pub(super) static CHAPTER_TITLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)(?:Vol.(?P<volume>\d+) )?Chapter (?P<id>\d+(?:\.\d+)?)"#)
+        .expect("invalid chapter regexp")
+});
+
 /// Extract every chapter listed in the given HTML.
 #[allow(clippy::filter_map)]
 pub(super) fn scrape_from_html<'a>(
     html: &kuchiki::NodeRef,
     series: &'a Series,
+    selectors: &Selectors,
 ) -> Result<Vec<Chapter<'a>>> {
-    CHAPTER_SELECTOR
+    selectors
+        .chapter
         .filter(html.descendants().elements())
         .map(|link| {
             let url = url_from_element(&link)?;
@@ -32,24 +42,57 @@ pub(super) fn scrape_from_html<'a>(
         .collect::<Result<Vec<_>>>()
 }
 
+/// Extract the chapter served at `url` from its own page.
+#[allow(clippy::filter_next)]
+pub(super) fn scrape_link_from_html(
+    html: &kuchiki::NodeRef,
+    url: &Url,
+    selectors: &Selectors,
+) -> Result<ChapterLink> {
+    let heading = selectors
+        .chapter_heading
+        .filter(html.descendants().elements())
+        .next()
+        .ok_or_else(|| Error::Scraping("chapter title not found".to_owned()))?
+        .text_contents();
+    let (id, volume) = parse_chapter_title(&heading)?;
+
+    let series = selectors
+        .chapter_series
+        .filter(html.descendants().elements())
+        .next()
+        .ok_or_else(|| Error::Scraping("series link not found".to_owned()))?;
+    let attributes = series.attributes.borrow();
+    let series = attributes
+        .get("href")
+        .ok_or_else(|| Error::Scraping("series URL not found".to_owned()))?;
+
+    Ok(ChapterLink {
+        series: Url::parse(series).map_err(|err| {
+            Error::Scraping(format!("invalid series URL `{}`: {}", series, err))
+        })?,
+        id,
+        volume,
+        url: url.clone(),
+    })
+}
+
 /// Extract chapter ID and volume name (if any) from chapter's title.
-#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
 fn parse_title(
     element: &kuchiki::ElementData,
 ) -> Result<(f64, Option<String>)> {
-    let extract_info = Lazy::new(|| {
-        Regex::new(
-            r#"(?i)(?:Vol.(?P<volume>\d+) )?Chapter (?P<id>\d+(?:\.\d+)?)"#,
-        )
-        .expect("invalid chapter regexp")
-    });
-
     let attributes = element.attributes.borrow();
     let title = attributes
         .get("title")
         .ok_or_else(|| Error::Scraping("chapter title not found".to_owned()))?;
 
-    let matches = extract_info.captures(&title).ok_or_else(|| {
+    parse_chapter_title(title)
+}
+
+/// Extract chapter ID and volume name (if any) from `title`.
+#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
+fn parse_chapter_title(title: &str) -> Result<(f64, Option<String>)> {
+    let matches = CHAPTER_TITLE_REGEX.captures(title).ok_or_else(|| {
         Error::Scraping(format!("cannot match on chapter title: {}", title))
     })?;
 
@@ -101,10 +144,36 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let chapters = scrape_from_html(&document, &series).unwrap();
+        let chapters =
+            scrape_from_html(&document, &series, &Selectors::default())
+                .unwrap();
 
         assert_eq!(chapters.len(), 330);
     }
+
+    #[test]
+    fn test_link_scraping() {
+        let url = Url::parse(
+            "https://mangakakalot.com/chapter/qrka108781556854403/chapter_42",
+        )
+        .unwrap();
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangakakalot.com/chapter.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let link =
+            scrape_link_from_html(&document, &url, &Selectors::default())
+                .unwrap();
+
+        assert_eq!(
+            link.series.as_str(),
+            "https://mangakakalot.com/read-lu8jl158504848312"
+        );
+        assert_eq!(link.id, 42.0);
+        assert_eq!(link.volume.as_deref(), Some("05"));
+        assert_eq!(link.url, url);
+    }
 }
 
 // }}}
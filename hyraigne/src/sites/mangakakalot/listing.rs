@@ -0,0 +1,45 @@
+use super::{
+    search,
+    selectors::Selectors,
+};
+use crate::{
+    Result,
+    SeriesSummary,
+};
+
+/// Scrape the series listed on a page of the series listing.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    search::scrape_links(html, &selectors.listing)
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangakakalot.com/latest.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let results =
+            scrape_from_html(&document, &Selectors::default()).unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1].title, "Higanjima");
+        assert_eq!(
+            results[1].url.as_str(),
+            "https://mangakakalot.com/read-lu8jl158504848312"
+        );
+    }
+}
+
+// }}}
@@ -1,4 +1,4 @@
-use super::selectors::PAGE_URL_SELECTOR;
+use super::selectors::Selectors;
 use crate::{
     Chapter,
     Error,
@@ -8,12 +8,20 @@ use crate::{
 use kuchiki::traits::*;
 use url::Url;
 
+/// Image servers behind the "Server 1/2" switch of the chapter pages.
+///
+/// They all host the same images, under the same path.
+const IMAGE_SERVERS: [&str; 2] =
+    ["s8.mkklcdnv6tempv3.com", "bu.mkklcdnbuv1.com"];
+
 /// Scrape page links from the chapter's page HTML.
 pub(super) fn scrape_from_html<'a>(
     html: &kuchiki::NodeRef,
     chapter: &'a Chapter<'_>,
+    selectors: &Selectors,
 ) -> Result<Vec<Page<'a>>> {
-    PAGE_URL_SELECTOR
+    selectors
+        .page_url
         .filter(html.descendants().elements())
         .enumerate()
         .map(|(idx, node)| {
@@ -30,13 +38,29 @@ pub(super) fn scrape_from_html<'a>(
             Ok(Page {
                 id: (idx + 1) as u16,
                 chapter,
+                fallbacks: alternate_urls(&url),
                 main: url,
-                fallback: None,
             })
         })
         .collect::<Result<Vec<_>>>()
 }
 
+/// Build the URLs of the image at `url` on the other image servers.
+fn alternate_urls(url: &Url) -> Vec<Url> {
+    IMAGE_SERVERS
+        .iter()
+        .filter_map(|&server| {
+            if url.host_str() == Some(server) {
+                return None;
+            }
+            let mut alternate = url.clone();
+            alternate.set_host(Some(server)).ok()?;
+
+            Some(alternate)
+        })
+        .collect()
+}
+
 // Tests {{{
 
 #[cfg(test)]
@@ -66,9 +90,32 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let pages = scrape_from_html(&document, &chapter).unwrap();
+        let pages =
+            scrape_from_html(&document, &chapter, &Selectors::default())
+                .unwrap();
 
         assert_eq!(pages.len(), 23);
+        assert_eq!(
+            pages[0].main.as_str(),
+            "https://s7.mkklcdnv6tempv3.com/mangakakalot/h1/higanjima/vol5_chapter_42_pleasure/1.jpg"
+        );
+        assert_eq!(
+            pages[0].fallbacks.iter().map(Url::as_str).collect::<Vec<_>>(),
+            [
+                "https://s8.mkklcdnv6tempv3.com/mangakakalot/h1/higanjima/vol5_chapter_42_pleasure/1.jpg",
+                "https://bu.mkklcdnbuv1.com/mangakakalot/h1/higanjima/vol5_chapter_42_pleasure/1.jpg",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alternate_urls() {
+        let url = Url::parse("https://bu.mkklcdnbuv1.com/h1/1.jpg").unwrap();
+
+        assert_eq!(
+            alternate_urls(&url),
+            [Url::parse("https://s8.mkklcdnv6tempv3.com/h1/1.jpg").unwrap()]
+        );
     }
 }
 
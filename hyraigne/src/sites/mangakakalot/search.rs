@@ -0,0 +1,72 @@
+use super::selectors::Selectors;
+use crate::{
+    Error,
+    Result,
+    SeriesSummary,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Scrape the series listed in the search results.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    scrape_links(html, &selectors.search_result)
+}
+
+/// Scrape the series links matched by `selector`.
+pub(super) fn scrape_links(
+    html: &kuchiki::NodeRef,
+    selector: &kuchiki::Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    selector
+        .filter(html.descendants().elements())
+        .map(|link| {
+            let title = link.text_contents().trim().to_owned();
+            let attributes = link.attributes.borrow();
+            let url = attributes.get("href").ok_or_else(|| {
+                Error::Scraping("series URL not found".to_owned())
+            })?;
+
+            Ok(SeriesSummary {
+                title,
+                url: Url::parse(url).map_err(|err| {
+                    Error::Scraping(format!(
+                        "invalid series URL `{}`: {}",
+                        url, err
+                    ))
+                })?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangakakalot.com/search.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let results =
+            scrape_from_html(&document, &Selectors::default()).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].title, "Tower Of God");
+        assert_eq!(
+            results[0].url.as_str(),
+            "https://mangakakalot.com/manga/tower_of_god"
+        );
+        assert_eq!(results[2].title, "The Tower Of God's Assistant");
+    }
+}
+
+// }}}
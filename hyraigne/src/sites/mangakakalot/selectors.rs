@@ -1,35 +1,144 @@
 //! CSS selectors to scrape `https://mangakakalot.com`.
 
-use once_cell::sync::Lazy;
-
-/// Select the series title.
-#[allow(clippy::expect_used)]
-pub(super) static SERIES_TITLE_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile(".manga-info-text h1")
-            .expect("invalid series title selector")
-    });
-
-/// Select `<meta property="og:url" content="URL" />`
-#[allow(clippy::expect_used)]
-pub(super) static SERIES_URL_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile("meta[property=\"og:url\"]")
-            .expect("invalid series URL selector")
-    });
-
-/// Select chapter links in the chapter list.
-#[allow(clippy::expect_used)]
-pub(super) static CHAPTER_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile(".chapter-list .row a")
-            .expect("invalid chapter selector")
-    });
-
-/// Select image URLs from the chapter page.
-#[allow(clippy::expect_used)]
-pub(super) static PAGE_URL_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile(".container-chapter-reader img")
-            .expect("invalid page URL selector")
-    });
+use super::chapter::CHAPTER_TITLE_REGEX;
+use crate::{
+    sites::SelectorOverrides,
+    SelectorCheck,
+};
+
+/// Name of the web spider, to look up the overrides.
+const SITE: &str = "mangakakalot";
+
+/// Name and default value of each selector.
+pub(in crate::sites) const DEFAULTS: [(&str, &str); 8] = [
+    ("series_title", ".manga-info-text h1"),
+    ("series_url", "meta[property=\"og:url\"]"),
+    ("chapter", ".chapter-list .row a"),
+    ("chapter_series", ".breadcrumb span:nth-child(3) > a"),
+    ("chapter_heading", ".current-chapter"),
+    ("page_url", ".container-chapter-reader img"),
+    ("listing", ".list-truyen-item-wrap h3 a"),
+    ("search_result", ".story_item .story_name a"),
+];
+
+/// CSS selectors to scrape `https://mangakakalot.com`.
+pub(super) struct Selectors {
+    /// Select the series title.
+    pub(super) series_title: kuchiki::Selectors,
+    /// Select `<meta property="og:url" content="URL" />`
+    pub(super) series_url: kuchiki::Selectors,
+    /// Select chapter links in the chapter list.
+    pub(super) chapter: kuchiki::Selectors,
+    /// Select the series link from the chapter page.
+    pub(super) chapter_series: kuchiki::Selectors,
+    /// Select the chapter title from the chapter page.
+    pub(super) chapter_heading: kuchiki::Selectors,
+    /// Select image URLs from the chapter page.
+    pub(super) page_url: kuchiki::Selectors,
+    /// Select series links in a series listing.
+    pub(super) listing: kuchiki::Selectors,
+    /// Select series links in the search results.
+    pub(super) search_result: kuchiki::Selectors,
+}
+
+impl Selectors {
+    /// Compile the selectors, applying the given overrides.
+    pub(super) fn new(overrides: &SelectorOverrides) -> Self {
+        let compile = |name| overrides.compile(SITE, name, &DEFAULTS);
+
+        Self {
+            series_title: compile("series_title"),
+            series_url: compile("series_url"),
+            chapter: compile("chapter"),
+            chapter_series: compile("chapter_series"),
+            chapter_heading: compile("chapter_heading"),
+            page_url: compile("page_url"),
+            listing: compile("listing"),
+            search_result: compile("search_result"),
+        }
+    }
+
+    /// Run every selector, and the chapter title regexp, against `html`.
+    pub(super) fn check(&self, html: &kuchiki::NodeRef) -> Vec<SelectorCheck> {
+        let titles = SelectorCheck::run(
+            "chapter_title",
+            &self.chapter,
+            html,
+            Some("title"),
+        );
+
+        vec![
+            SelectorCheck::run("series_title", &self.series_title, html, None),
+            SelectorCheck::run(
+                "series_url",
+                &self.series_url,
+                html,
+                Some("content"),
+            ),
+            SelectorCheck::run("chapter", &self.chapter, html, Some("href")),
+            SelectorCheck::run_regex(
+                "chapter_title_regex",
+                &CHAPTER_TITLE_REGEX,
+                titles.values().iter().map(String::as_str),
+            ),
+            SelectorCheck::run(
+                "chapter_series",
+                &self.chapter_series,
+                html,
+                Some("href"),
+            ),
+            SelectorCheck::run(
+                "chapter_heading",
+                &self.chapter_heading,
+                html,
+                None,
+            ),
+            SelectorCheck::run("page_url", &self.page_url, html, Some("src")),
+            SelectorCheck::run("listing", &self.listing, html, Some("href")),
+            SelectorCheck::run(
+                "search_result",
+                &self.search_result,
+                html,
+                Some("href"),
+            ),
+        ]
+    }
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self::new(&SelectorOverrides::default())
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/mangakakalot.com/series.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let checks = Selectors::default().check(&document);
+        let check = |name| {
+            checks
+                .iter()
+                .find(|check| check.name() == name)
+                .expect("check")
+        };
+
+        assert_eq!(check("series_title").values(), ["Higanjima"]);
+        assert_eq!(check("chapter").matches(), 330);
+        assert_eq!(check("chapter_title_regex").matches(), 330);
+        assert_eq!(check("page_url").matches(), 0);
+    }
+}
+
+// }}}
@@ -1,141 +1,434 @@
 use super::{
     chapter,
+    listing,
     page,
+    search,
+    selectors::Selectors,
     series,
 };
 use crate::{
-    spiders::HttpClient,
+    sites::{
+        hosts,
+        scraper::{
+            self,
+            ChapterLink,
+            Document,
+            Scraper,
+        },
+    },
     Chapter,
-    Error,
     Filter,
+    LinkKind,
+    Listing,
     Options,
     Page,
     Result,
+    SelectorCheck,
     Series,
+    SeriesSummary,
 };
-use std::path::PathBuf;
+use kuchiki::traits::*;
 use url::Url;
 
 /// A web spider for `https://mangakakalot.com`.
 pub(crate) struct Site {
-    spider: HttpClient,
-    output: PathBuf,
+    selectors: Selectors,
 }
 
 impl Site {
     /// Initialize the web spider with the given options.
-    pub(crate) fn new(options: Options) -> Self {
+    pub(crate) fn new(options: &Options) -> Self {
         Self {
-            spider: HttpClient::new(options.delay, options.retry, None),
-            output: options.output,
+            selectors: Selectors::new(&options.selectors),
         }
     }
 }
 
-impl crate::Site for Site {
-    fn get_series(&self, url: &Url) -> Result<Series> {
-        log::info!("scraping series info from {}…", url.as_str());
+impl Scraper for Site {
+    fn search_url(&self, query: &str) -> Result<Url> {
+        Ok(search_url(query))
+    }
+
+    fn scrape_search(&self, document: &Document) -> Result<Vec<SeriesSummary>> {
+        search::scrape_from_html(document.html()?, &self.selectors)
+    }
+
+    fn listing_url(&self, listing: Listing, page: u16) -> Result<Option<Url>> {
+        Ok(Some(listing_url(listing, page)))
+    }
+
+    fn scrape_listing(
+        &self,
+        _listing: Listing,
+        document: &Document,
+    ) -> Result<Vec<SeriesSummary>> {
+        listing::scrape_from_html(document.html()?, &self.selectors)
+    }
+
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        let html = kuchiki::parse_html().one(html);
+
+        Ok(self.selectors.check(&html))
+    }
+
+    fn canonical_url(&self, url: &Url) -> Url {
+        hosts::canonicalize(url, "mangakakalot.com", &[])
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        if url.path().starts_with("/chapter/") {
+            LinkKind::Chapter
+        } else {
+            LinkKind::Series
+        }
+    }
 
-        let html = self.spider.get_html(url)?;
-        let series = series::scrape_from_html(&html).map_err(|err| {
-            Error::Scraping(format!(
-                "failed to scrape serie from {}: {}",
-                url.as_str(),
-                err
-            ))
-        })?;
+    fn scrape_chapter(
+        &self,
+        url: &Url,
+        document: &Document,
+    ) -> Result<ChapterLink> {
+        chapter::scrape_link_from_html(document.html()?, url, &self.selectors)
+    }
 
-        Ok(series)
+    fn scrape_series(&self, _url: &Url, document: &Document) -> Result<Series> {
+        series::scrape_from_html(document.html()?, &self.selectors)
     }
 
-    fn get_chapters<'a>(
+    fn scrape_chapters<'a>(
         &self,
+        _url: &Url,
         series: &'a Series,
-        filter: Filter,
+        _filter: &Filter,
+        document: &Document,
     ) -> Result<Vec<Chapter<'a>>> {
-        log::info!("scraping chapter links for series {}…", series.title);
-
-        let html = self.spider.get_html(&series.url)?;
-        let chapters =
-            chapter::scrape_from_html(&html, &series).map_err(|err| {
-                Error::Scraping(format!(
-                    "failed to scrape chapters from {}: {}",
-                    series.url.as_str(),
-                    err
-                ))
-            })?;
-        log::debug!("found {} chapters", chapters.len());
-
-        // Trim the chapters list to keep only the requested chapters.
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        let mut chapters = chapters
-            .into_iter()
-            // Chapter IDs are always positive and under u16::MAX.
-            .filter(|chapter| filter.range.contains(&(chapter.id as u16)))
-            .collect::<Vec<_>>();
-
-        #[allow(clippy::expect_used)] // Shouldn't have NaN & friends here…
-        chapters
-            .sort_by(|a, b| a.partial_cmp(b).expect("abnormal float as ID"));
-        log::debug!("selected {} chapters", chapters.len());
-
-        Ok(chapters)
-    }
-
-    fn get_pages<'a>(&self, chapter: &'a Chapter<'_>) -> Result<Vec<Page<'a>>> {
-        log::info!("scraping page links for chapter {}…", chapter.id);
-
-        let html = self.spider.get_html(&chapter.url)?;
-        let pages = page::scrape_from_html(&html, chapter).map_err(|err| {
-            Error::Scraping(format!(
-                "failed to pages from {}: {}",
-                chapter.url.as_str(),
-                err
-            ))
-        })?;
-
-        log::debug!("found {} pages in chapter {}", pages.len(), chapter.id);
-
-        Ok(pages)
-    }
-
-    fn mkdir(&self, chapters: &[Chapter<'_>]) -> Result<()> {
-        for chapter in chapters {
-            let path = chapter.path(&self.output);
-            crate::fs::mkdir_p(&path)?;
+        chapter::scrape_from_html(document.html()?, series, &self.selectors)
+    }
+
+    fn keep_chapter(&self, chapter: &Chapter<'_>, filter: &Filter) -> bool {
+        scraper::keep_truncated_id(chapter, filter)
+    }
+
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        page::scrape_from_html(document.html()?, chapter, &self.selectors)
+    }
+}
+
+/// Build the URL of the search results for `query`.
+///
+/// Mangakakalot expects the query to be lowercased, with every non
+/// alphanumeric character replaced by an underscore.
+#[allow(clippy::expect_used)] // Hardcoded values should be valid…
+fn search_url(query: &str) -> Url {
+    let keywords = query
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    Url::parse("https://mangakakalot.com/search/story/")
+        .expect("valid URL")
+        .join(&keywords)
+        .expect("valid search URL")
+}
+
+/// Build the URL of the `page`-th page of the given listing.
+///
+/// Those are the full listings linked from the "Latest updates" and "Popular"
+/// sections of the homepage.
+#[allow(clippy::expect_used)] // Hardcoded values should be valid…
+fn listing_url(listing: Listing, page: u16) -> Url {
+    let kind = match listing {
+        Listing::Latest => "latest",
+        Listing::Popular => "topview",
+    };
+    let mut url =
+        Url::parse("https://mangakakalot.com/manga_list").expect("valid URL");
+    url.query_pairs_mut()
+        .append_pair("type", kind)
+        .append_pair("category", "all")
+        .append_pair("state", "all")
+        .append_pair("page", &page.to_string());
+
+    url
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::engine::Engine,
+        testing,
+        CancellationToken,
+        Error,
+        Event,
+        MemoryTransport,
+        Response,
+        Site as _,
+    };
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    /// Build the blocking web spider.
+    fn spider(options: Options) -> Engine<Site> {
+        Engine::new(Site::new(&options), options)
+    }
+
+    #[test]
+    fn test_search_url() {
+        let url = search_url(" Tower of God ");
+
+        assert_eq!(
+            url.as_str(),
+            "https://mangakakalot.com/search/story/tower_of_god"
+        );
+    }
+
+    #[test]
+    fn test_canonical_url() {
+        let site = Site::new(&Options::new(0, 0, std::env::temp_dir()));
+        let canonical_url =
+            |url| site.canonical_url(&Url::parse(url).unwrap()).to_string();
+
+        assert_eq!(
+            canonical_url(
+                "https://www.mangakakalot.com/read-lu8jl158504848312"
+            ),
+            "https://mangakakalot.com/read-lu8jl158504848312"
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://mangakakalot.com/search/story/tower_of_god",
+            "mangakakalot.com/search.html",
+        );
+        let site = spider(testing::options(&transport, "mk-search"));
+
+        let results = site.search("Tower of God").unwrap();
+
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn test_browse() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://mangakakalot.com/manga_list?type=latest&category=all&state=all&page=1",
+            "mangakakalot.com/latest.html",
+        );
+        let site = spider(testing::options(&transport, "mk-browse"));
+
+        let results = site.browse(Listing::Latest, 1).unwrap();
+
+        assert_eq!(results.len(), 4);
+    }
+
+    #[test]
+    fn test_download() {
+        let transport = Arc::new(MemoryTransport::new());
+        let site = spider(testing::options(&transport, "mk-download"));
+        let pages = testing::mangakakalot_pages(&site, &transport);
+        assert_eq!(pages.len(), 23);
+
+        for page in &pages {
+            transport.insert(&page.main, Response::new(200, "image"));
         }
+        site.mkdir(std::slice::from_ref(pages[0].chapter)).unwrap();
+        site.download(&pages).unwrap();
 
-        Ok(())
+        for page in &pages {
+            let path = page.path(&site.output);
+            assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        }
     }
 
-    fn download(&self, pages: &[Page<'_>]) -> Result<()> {
-        log::info!(
-            "downloading {} pages for chapter {}…",
-            pages.len(),
-            pages[0].chapter.id,
+    #[test]
+    fn test_download_image_servers() {
+        let transport = Arc::new(MemoryTransport::new());
+        let site = spider(testing::options(&transport, "mk-servers"));
+        let pages = testing::mangakakalot_pages(&site, &transport);
+        // Only the last server is up.
+        for page in &pages {
+            transport.insert(&page.fallbacks[1], Response::new(200, "image"));
+        }
+        site.mkdir(std::slice::from_ref(pages[0].chapter)).unwrap();
+        let requests = transport.requests().len();
+        site.download(&pages).unwrap();
+
+        for page in &pages {
+            let path = page.path(&site.output);
+            assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        }
+        // The broken servers are only tried for the first page.
+        let requests = transport.requests().split_off(requests);
+        assert_eq!(requests.len(), pages.len() + 2);
+        assert_eq!(requests[0], pages[0].main);
+        assert_eq!(requests[1], pages[0].fallbacks[0]);
+        assert_eq!(requests[3], pages[1].fallbacks[1]);
+    }
+
+    #[test]
+    fn test_get_chapter_from_link() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://mangakakalot.com/chapter/qrka108781556854403/chapter_42",
+            "mangakakalot.com/chapter.html",
         );
+        testing::serve(
+            &transport,
+            "https://mangakakalot.com/read-lu8jl158504848312",
+            "mangakakalot.com/series.html",
+        );
+        let site = spider(testing::options(&transport, "mk-chapter-link"));
+        let url = Url::parse(
+            "https://mangakakalot.com/chapter/qrka108781556854403/chapter_42",
+        )
+        .unwrap();
 
-        let mut bytes: Vec<u8> = Vec::new();
+        assert_eq!(site.classify(&url), LinkKind::Chapter);
+        let series = site.get_series(&url).unwrap();
+        assert_eq!(series.title, "Higanjima");
 
-        for page in pages.iter() {
-            // Compute the image path.
-            let path = page.path(&self.output);
+        let chapter = site.get_chapter(&series, &url).unwrap();
+        assert_eq!(chapter.id, 42.0);
+        assert_eq!(chapter.url, url);
 
-            // Skip it if it has already been downloaded.
-            if path.exists() {
-                log::debug!("{} already exists, skip", path.display());
-                continue;
-            }
+        let series_url = series.url.clone();
+        assert!(matches!(
+            site.get_chapter(&series, &series_url),
+            Err(Error::UnsupportedUrl { .. })
+        ));
+    }
 
-            log::info!("downloading {}…", path.display());
-            self.spider
-                .get_image(&page.main, &page.chapter.url, &mut bytes)?;
+    #[test]
+    fn test_download_events() {
+        let transport = Arc::new(MemoryTransport::new());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let opts = testing::options(&transport, "mk-events")
+            .with_event_handler(Arc::new(move |event: &Event| {
+                sink.lock().unwrap().push(event.clone());
+            }));
+        let site = spider(opts);
+        let pages = testing::mangakakalot_pages(&site, &transport);
+        // First page already downloaded, third one missing.
+        transport.insert(&pages[1].main, Response::new(200, "image"));
+        site.mkdir(std::slice::from_ref(pages[0].chapter)).unwrap();
+        std::fs::write(pages[0].path(&site.output), b"image").unwrap();
+        site.download(&pages).unwrap_err();
 
-            crate::fs::atomic_save(&path, &bytes)?;
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            events[0],
+            Event::SeriesResolved { ref title, .. } if title == "Higanjima"
+        ));
+        assert!(matches!(events[1], Event::ChapterListed { .. }));
+        assert!(matches!(events[2], Event::ChapterStarted { pages: 23, .. }));
+        assert!(matches!(events[3], Event::PageSkipped { page: 1, .. }));
+        assert!(matches!(
+            events[4],
+            Event::PageDownloaded {
+                page: 2,
+                bytes: 5,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[5],
+            Event::Error {
+                kind: "not_found",
+                url: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(events.len(), 6);
+    }
 
-            bytes.clear();
+    #[test]
+    fn test_download_cancelled() {
+        let transport = Arc::new(MemoryTransport::new());
+        // Stop after the first page.
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let opts = testing::options(&transport, "mk-cancel")
+            .with_cancellation(token)
+            .with_event_handler(Arc::new(move |event: &Event| {
+                if let Event::PageDownloaded { .. } = *event {
+                    canceller.cancel();
+                }
+            }));
+        let site = spider(opts);
+        let pages = testing::mangakakalot_pages(&site, &transport);
+        for page in &pages {
+            transport.insert(&page.main, Response::new(200, "image"));
         }
+        site.mkdir(std::slice::from_ref(pages[0].chapter)).unwrap();
+        // Left over by an interrupted download.
+        let leftover = crate::fs::tmp_path(&pages[1].path(&site.output));
+        std::fs::write(&leftover, b"ima").unwrap();
+
+        let res = site.download(&pages);
 
-        Ok(())
+        assert!(matches!(res, Err(Error::Cancelled)));
+        assert!(pages[0].path(&site.output).exists());
+        assert!(!pages[1].path(&site.output).exists());
+        assert!(!leftover.exists());
+        assert!(site.get_series(&pages[0].chapter.series.url).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_download() {
+        use crate::{
+            sites::async_engine::AsyncEngine,
+            AsyncSite,
+            AsyncTransport,
+        };
+
+        let transport = Arc::new(MemoryTransport::new());
+        let opts = testing::options(&transport, "mk-async")
+            .with_async_transport(
+                Arc::clone(&transport) as Arc<dyn AsyncTransport>
+            );
+        let site = AsyncEngine::new(Site::new(&opts), opts);
+
+        testing::block_on(async {
+            let pages =
+                testing::mangakakalot_pages_async(&site, &transport).await;
+            assert_eq!(pages.len(), 23);
+
+            for page in &pages {
+                transport.insert(&page.main, Response::new(200, "image"));
+            }
+            site.mkdir(std::slice::from_ref(pages[0].chapter)).unwrap();
+            site.download(&pages).await.unwrap();
+
+            for page in &pages {
+                let path = page.path(&site.output);
+                assert_eq!(std::fs::read(&path).unwrap(), b"image");
+            }
+        });
     }
 }
+
+// }}}
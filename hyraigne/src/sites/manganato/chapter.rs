@@ -0,0 +1,180 @@
+use super::selectors::Selectors;
+use crate::{
+    sites::scraper::ChapterLink,
+    Chapter,
+    Error,
+    Result,
+    Series,
+};
+use kuchiki::traits::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use url::Url;
+
+/// Extract chapter ID and volume (if any) from chapter's title.
+#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
+pub(super) static CHAPTER_TITLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)(?:Vol.(?P<volume>\d+) )?Chapter (?P<id>\d+(?:\.\d+)?)"#)
+        .expect("invalid chapter regexp")
+});
+
+/// Extract every chapter listed in the given HTML.
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    series: &'a Series,
+    selectors: &Selectors,
+) -> Result<Vec<Chapter<'a>>> {
+    selectors
+        .chapter
+        .filter(html.descendants().elements())
+        .map(|link| {
+            let url = url_from_element(&link)?;
+            // Unlike MangaKakalot, the `title` attribute is prefixed by the
+            // series title: rely on the link text instead.
+            let (id, volume) = parse_title(&link.text_contents())?;
+
+            Ok(Chapter {
+                id,
+                series,
+                volume,
+                url,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Extract the chapter served at `url` from its own page.
+#[allow(clippy::filter_next)]
+pub(super) fn scrape_link_from_html(
+    html: &kuchiki::NodeRef,
+    url: &Url,
+    selectors: &Selectors,
+) -> Result<ChapterLink> {
+    let heading = selectors
+        .chapter_heading
+        .filter(html.descendants().elements())
+        .next()
+        .ok_or_else(|| Error::Scraping("chapter title not found".to_owned()))?
+        .text_contents();
+    let (id, volume) = parse_title(&heading)?;
+
+    let series = selectors
+        .chapter_series
+        .filter(html.descendants().elements())
+        .next()
+        .ok_or_else(|| Error::Scraping("series link not found".to_owned()))?;
+    let attributes = series.attributes.borrow();
+    let series = attributes
+        .get("href")
+        .ok_or_else(|| Error::Scraping("series URL not found".to_owned()))?;
+
+    Ok(ChapterLink {
+        series: Url::parse(series).map_err(|err| {
+            Error::Scraping(format!("invalid series URL `{}`: {}", series, err))
+        })?,
+        id,
+        volume,
+        url: url.clone(),
+    })
+}
+
+/// Extract chapter ID and volume name (if any) from `title`.
+#[allow(clippy::expect_used)] // Regexp is hardcoded and valid.
+fn parse_title(title: &str) -> Result<(f64, Option<String>)> {
+    let matches = CHAPTER_TITLE_REGEX.captures(title).ok_or_else(|| {
+        Error::Scraping(format!("cannot match on chapter title: {}", title))
+    })?;
+
+    let volume = matches
+        .name("volume")
+        .map(|m| format!("{:0>2}", m.as_str()));
+    let id = matches
+        .name("id")
+        .expect("invalid capture group for chapter ID")
+        .as_str()
+        .parse::<f64>()
+        .map_err(|err| {
+            Error::Scraping(format!("invalid chapter ID: {}", err))
+        })?;
+
+    Ok((id, volume))
+}
+
+/// Extract the chapter URL.
+fn url_from_element(element: &kuchiki::ElementData) -> Result<Url> {
+    let attributes = element.attributes.borrow();
+    let url = attributes
+        .get("href")
+        .ok_or_else(|| Error::Scraping("chapter URL not found".to_owned()))?;
+
+    Url::parse(url).map_err(|err| {
+        Error::Scraping(format!("invalid chapter URL `{}`: {}", url, err))
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Pagination;
+    use std::path::PathBuf;
+
+    /// Parse the fixture at `testdata/readmanganato.com/<name>`.
+    fn load(name: &str) -> kuchiki::NodeRef {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/readmanganato.com");
+        path.push(name);
+        let html = std::fs::read_to_string(&path).expect("test data");
+
+        kuchiki::parse_html().one(html)
+    }
+
+    #[test]
+    fn test_scraping() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(0, 0),
+        };
+        let document = load("series.html");
+
+        let chapters =
+            scrape_from_html(&document, &series, &Selectors::default())
+                .unwrap();
+
+        let ids = chapters
+            .iter()
+            .map(|chapter| chapter.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [179.0, 178.0, 177.0, 110.5, 2.0, 1.0]);
+        assert_eq!(chapters[4].volume.as_deref(), Some("01"));
+        assert_eq!(chapters[0].volume, None);
+        assert_eq!(
+            chapters[3].url.as_str(),
+            "https://readmanganato.com/manga-dr980474/chapter-110.5"
+        );
+    }
+
+    #[test]
+    fn test_link_scraping() {
+        let url =
+            Url::parse("https://chapmanganato.com/manga-dr980474/chapter-179")
+                .unwrap();
+        let document = load("chapter.html");
+
+        let link =
+            scrape_link_from_html(&document, &url, &Selectors::default())
+                .unwrap();
+
+        assert_eq!(
+            link.series.as_str(),
+            "https://readmanganato.com/manga-dr980474"
+        );
+        assert_eq!(link.id, 179.0);
+        assert_eq!(link.volume, None);
+        assert_eq!(link.url, url);
+    }
+}
+
+// }}}
@@ -4,4 +4,5 @@ mod selectors;
 mod series;
 mod site;
 
+pub(super) use selectors::DEFAULTS as DEFAULT_SELECTORS;
 pub(crate) use site::Site;
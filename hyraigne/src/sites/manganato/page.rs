@@ -0,0 +1,113 @@
+use super::selectors::Selectors;
+use crate::{
+    Chapter,
+    Error,
+    Page,
+    Result,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Image server to fall back on when the main one fails.
+///
+/// It mirrors every image of the main servers, under the same path.
+const BACKUP_IMAGE_SERVER: &str = "bu.mkklcdnbuv1.com";
+
+/// Scrape page links from the chapter's page HTML.
+pub(super) fn scrape_from_html<'a>(
+    html: &kuchiki::NodeRef,
+    chapter: &'a Chapter<'_>,
+    selectors: &Selectors,
+) -> Result<Vec<Page<'a>>> {
+    selectors
+        .page_url
+        .filter(html.descendants().elements())
+        .enumerate()
+        .map(|(idx, node)| {
+            let attributes = node.attributes.borrow();
+            let url = attributes.get("src").ok_or_else(|| {
+                Error::Scraping("page URL not found".to_owned())
+            })?;
+
+            let url = Url::parse(url).map_err(|err| {
+                Error::Scraping(format!("invalid page URL `{}`: {}", url, err))
+            })?;
+
+            #[allow(clippy::cast_possible_truncation)] // Page number is small.
+            Ok(Page {
+                id: (idx + 1) as u16,
+                chapter,
+                fallbacks: backup_url(&url).into_iter().collect(),
+                main: url,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Move the image at `url` to the backup server.
+///
+/// `None` is returned if the image is already served from there.
+fn backup_url(url: &Url) -> Option<Url> {
+    if url.host_str() == Some(BACKUP_IMAGE_SERVER) {
+        return None;
+    }
+
+    let mut backup = url.clone();
+    backup.set_host(Some(BACKUP_IMAGE_SERVER)).ok()?;
+
+    Some(backup)
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::Pagination,
+        Series,
+    };
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let series = Series {
+            title: "Example".to_owned(),
+            url: Url::parse("http://example.com/").unwrap(),
+            pagination: Pagination::new(64, u16::MAX),
+        };
+        let chapter = Chapter {
+            id: 179.0,
+            series: &series,
+            volume: None,
+            url: Url::parse("http://example.com/179/").unwrap(),
+        };
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/readmanganato.com/chapter.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let pages =
+            scrape_from_html(&document, &chapter, &Selectors::default())
+                .unwrap();
+
+        assert_eq!(pages.len(), 5);
+        assert_eq!(
+            pages[0].main.as_str(),
+            "https://v12.mkklcdnv6tempv2.com/img/tab_12/00/49/34/dr980474/chapter_179/1-o.jpg"
+        );
+        assert_eq!(
+            pages[0].fallbacks,
+            [Url::parse("https://bu.mkklcdnbuv1.com/img/tab_12/00/49/34/dr980474/chapter_179/1-o.jpg").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_backup_url() {
+        let url = Url::parse("https://bu.mkklcdnbuv1.com/img/1-o.jpg").unwrap();
+
+        assert_eq!(backup_url(&url), None);
+    }
+}
+
+// }}}
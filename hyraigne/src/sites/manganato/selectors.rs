@@ -0,0 +1,131 @@
+//! CSS selectors to scrape `https://manganato.com`.
+
+use super::chapter::CHAPTER_TITLE_REGEX;
+use crate::{
+    sites::SelectorOverrides,
+    SelectorCheck,
+};
+
+/// Name of the web spider, to look up the overrides.
+const SITE: &str = "manganato";
+
+/// Name and default value of each selector.
+pub(in crate::sites) const DEFAULTS: [(&str, &str); 6] = [
+    ("series_title", ".story-info-right h1"),
+    ("series_url", "meta[property=\"og:url\"]"),
+    ("chapter", ".row-content-chapter a.chapter-name"),
+    ("chapter_series", ".panel-breadcrumb a:nth-of-type(2)"),
+    ("chapter_heading", ".panel-chapter-info-top h1"),
+    ("page_url", ".container-chapter-reader img"),
+];
+
+/// CSS selectors to scrape `https://manganato.com`.
+pub(super) struct Selectors {
+    /// Select the series title.
+    pub(super) series_title: kuchiki::Selectors,
+    /// Select `<meta property="og:url" content="URL" />`
+    pub(super) series_url: kuchiki::Selectors,
+    /// Select chapter links in the chapter list.
+    pub(super) chapter: kuchiki::Selectors,
+    /// Select the series link from the chapter page.
+    pub(super) chapter_series: kuchiki::Selectors,
+    /// Select the chapter title from the chapter page.
+    pub(super) chapter_heading: kuchiki::Selectors,
+    /// Select image URLs from the chapter page.
+    pub(super) page_url: kuchiki::Selectors,
+}
+
+impl Selectors {
+    /// Compile the selectors, applying the given overrides.
+    pub(super) fn new(overrides: &SelectorOverrides) -> Self {
+        let compile = |name| overrides.compile(SITE, name, &DEFAULTS);
+
+        Self {
+            series_title: compile("series_title"),
+            series_url: compile("series_url"),
+            chapter: compile("chapter"),
+            chapter_series: compile("chapter_series"),
+            chapter_heading: compile("chapter_heading"),
+            page_url: compile("page_url"),
+        }
+    }
+
+    /// Run every selector, and the chapter title regexp, against `html`.
+    pub(super) fn check(&self, html: &kuchiki::NodeRef) -> Vec<SelectorCheck> {
+        let titles =
+            SelectorCheck::run("chapter_title", &self.chapter, html, None);
+
+        vec![
+            SelectorCheck::run("series_title", &self.series_title, html, None),
+            SelectorCheck::run(
+                "series_url",
+                &self.series_url,
+                html,
+                Some("content"),
+            ),
+            SelectorCheck::run("chapter", &self.chapter, html, Some("href")),
+            SelectorCheck::run_regex(
+                "chapter_title_regex",
+                &CHAPTER_TITLE_REGEX,
+                titles.values().iter().map(String::as_str),
+            ),
+            SelectorCheck::run(
+                "chapter_series",
+                &self.chapter_series,
+                html,
+                Some("href"),
+            ),
+            SelectorCheck::run(
+                "chapter_heading",
+                &self.chapter_heading,
+                html,
+                None,
+            ),
+            SelectorCheck::run("page_url", &self.page_url, html, Some("src")),
+        ]
+    }
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self::new(&SelectorOverrides::default())
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/readmanganato.com/chapter.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let checks = Selectors::default().check(&document);
+        let check = |name| {
+            checks
+                .iter()
+                .find(|check| check.name() == name)
+                .expect("check")
+        };
+
+        assert_eq!(
+            check("chapter_series").values(),
+            ["https://readmanganato.com/manga-dr980474"]
+        );
+        assert_eq!(
+            check("chapter_heading").values(),
+            ["SOLO LEVELING CHAPTER 179"]
+        );
+        assert_eq!(check("page_url").matches(), 5);
+        assert_eq!(check("chapter").matches(), 0);
+    }
+}
+
+// }}}
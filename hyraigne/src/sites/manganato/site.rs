@@ -0,0 +1,316 @@
+use super::{
+    chapter,
+    page,
+    selectors::Selectors,
+    series,
+};
+use crate::{
+    sites::{
+        hosts,
+        scraper::{
+            self,
+            ChapterLink,
+            Document,
+            Scraper,
+        },
+    },
+    Chapter,
+    Filter,
+    LinkKind,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// A web spider for `https://manganato.com`.
+///
+/// Series are served from `readmanganato.com` (and `chapmanganato.com` for
+/// the most recent ones).
+pub(crate) struct Site {
+    selectors: Selectors,
+}
+
+impl Site {
+    /// Initialize the web spider with the given options.
+    pub(crate) fn new(options: &Options) -> Self {
+        Self {
+            selectors: Selectors::new(&options.selectors),
+        }
+    }
+}
+
+impl Scraper for Site {
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        let html = kuchiki::parse_html().one(html);
+
+        Ok(self.selectors.check(&html))
+    }
+
+    fn canonical_url(&self, url: &Url) -> Url {
+        if hosts::same(url.host_str().unwrap_or_default(), "manganelo.com") {
+            if let Some(url) = from_manganelo(url) {
+                return url;
+            }
+        }
+
+        hosts::canonicalize(url, "readmanganato.com", &["manganato.com"])
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        let mut segments = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty());
+
+        match (segments.next(), segments.next()) {
+            (Some(series), Some(chapter))
+                if series.starts_with("manga-")
+                    && chapter.starts_with("chapter-") =>
+            {
+                LinkKind::Chapter
+            },
+            _ => LinkKind::Series,
+        }
+    }
+
+    fn scrape_chapter(
+        &self,
+        url: &Url,
+        document: &Document,
+    ) -> Result<ChapterLink> {
+        chapter::scrape_link_from_html(document.html()?, url, &self.selectors)
+    }
+
+    fn scrape_series(&self, _url: &Url, document: &Document) -> Result<Series> {
+        series::scrape_from_html(document.html()?, &self.selectors)
+    }
+
+    fn scrape_chapters<'a>(
+        &self,
+        _url: &Url,
+        series: &'a Series,
+        _filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        chapter::scrape_from_html(document.html()?, series, &self.selectors)
+    }
+
+    fn keep_chapter(&self, chapter: &Chapter<'_>, filter: &Filter) -> bool {
+        scraper::keep_truncated_id(chapter, filter)
+    }
+
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        page::scrape_from_html(document.html()?, chapter, &self.selectors)
+    }
+}
+
+/// Move a `manganelo.com` URL to `readmanganato.com`, its new domain.
+///
+/// The layout changed along the way: `/manga/ID` became `/manga-ID`, and
+/// `/chapter/ID/chapter_N` became `/manga-ID/chapter-N`.
+fn from_manganelo(url: &Url) -> Option<Url> {
+    let segments = url
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let path = match *segments.as_slice() {
+        ["manga", id] => format!("/manga-{}", id),
+        ["chapter", id, chapter] => {
+            format!("/manga-{}/{}", id, chapter.replace('_', "-"))
+        },
+        _ => return None,
+    };
+
+    let mut url = url.clone();
+    url.set_host(Some("readmanganato.com")).ok()?;
+    url.set_path(&path);
+
+    Some(url)
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::engine::Engine,
+        testing,
+        Error,
+        MemoryTransport,
+        Response,
+        Site as _,
+    };
+    use std::sync::Arc;
+
+    const SERIES_URL: &str = "https://readmanganato.com/manga-dr980474";
+    const CHAPTER_URL: &str =
+        "https://readmanganato.com/manga-dr980474/chapter-179";
+
+    /// Build the blocking web spider.
+    fn spider(options: Options) -> Engine<Site> {
+        Engine::new(Site::new(&options), options)
+    }
+
+    #[test]
+    fn test_canonical_url() {
+        let site = Site::new(&Options::new(0, 0, std::env::temp_dir()));
+        let canonical_url =
+            |url| site.canonical_url(&Url::parse(url).unwrap()).to_string();
+
+        assert_eq!(
+            canonical_url("https://manganato.com/manga-xn921310"),
+            "https://readmanganato.com/manga-xn921310"
+        );
+        assert_eq!(
+            canonical_url("https://manganelo.com/manga/xn921310"),
+            "https://readmanganato.com/manga-xn921310"
+        );
+        assert_eq!(
+            canonical_url("https://manganelo.com/chapter/xn921310/chapter_12"),
+            "https://readmanganato.com/manga-xn921310/chapter-12"
+        );
+        assert_eq!(
+            canonical_url(
+                "https://chapmanganato.com/manga-xn921310/chapter-12"
+            ),
+            "https://chapmanganato.com/manga-xn921310/chapter-12"
+        );
+    }
+
+    #[test]
+    fn test_classify() {
+        let site = Site::new(&Options::new(0, 0, std::env::temp_dir()));
+        let classify = |url| site.classify(&Url::parse(url).unwrap());
+
+        assert_eq!(classify(SERIES_URL), LinkKind::Series);
+        assert_eq!(classify(CHAPTER_URL), LinkKind::Chapter);
+        assert_eq!(
+            classify("https://chapmanganato.com/manga-xn921310/chapter-1.5/"),
+            LinkKind::Chapter
+        );
+    }
+
+    #[test]
+    fn test_download() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "readmanganato.com/series.html");
+        testing::serve(
+            &transport,
+            CHAPTER_URL,
+            "readmanganato.com/chapter.html",
+        );
+        let site = spider(testing::options(&transport, "mn-download"));
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        assert_eq!(series.title, "Solo Leveling");
+
+        let filter = Filter::new(179..=179, None, Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+        assert_eq!(chapters.len(), 1);
+
+        let pages = site.get_pages(&chapters[0]).unwrap();
+        assert_eq!(pages.len(), 5);
+
+        // The main image server is down for the first page.
+        for page in &pages[1..] {
+            transport.insert(&page.main, Response::new(200, "image"));
+        }
+        transport.insert(&pages[0].fallbacks[0], Response::new(200, "backup"));
+        site.mkdir(&chapters).unwrap();
+        site.download(&pages).unwrap();
+
+        assert_eq!(
+            std::fs::read(pages[0].path(&site.output)).unwrap(),
+            b"backup"
+        );
+        for page in &pages[1..] {
+            let path = page.path(&site.output);
+            assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        }
+    }
+
+    #[test]
+    fn test_get_chapter_from_link() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "https://chapmanganato.com/manga-dr980474/chapter-179",
+            "readmanganato.com/chapter.html",
+        );
+        testing::serve(&transport, SERIES_URL, "readmanganato.com/series.html");
+        let site = spider(testing::options(&transport, "mn-chapter-link"));
+        let url =
+            Url::parse("https://chapmanganato.com/manga-dr980474/chapter-179")
+                .unwrap();
+
+        let series = site.get_series(&url).unwrap();
+        assert_eq!(series.title, "Solo Leveling");
+
+        let chapter = site.get_chapter(&series, &url).unwrap();
+        assert_eq!(chapter.id, 179.0);
+        assert_eq!(chapter.url, url);
+
+        let series_url = series.url.clone();
+        assert!(matches!(
+            site.get_chapter(&series, &series_url),
+            Err(Error::UnsupportedUrl { .. })
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_download() {
+        use crate::{
+            sites::async_engine::AsyncEngine,
+            AsyncSite,
+            AsyncTransport,
+        };
+
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "readmanganato.com/series.html");
+        testing::serve(
+            &transport,
+            CHAPTER_URL,
+            "readmanganato.com/chapter.html",
+        );
+        let opts = testing::options(&transport, "mn-async")
+            .with_async_transport(
+                Arc::clone(&transport) as Arc<dyn AsyncTransport>
+            );
+        let site = AsyncEngine::new(Site::new(&opts), opts);
+        let url = Url::parse(SERIES_URL).unwrap();
+
+        testing::block_on(async {
+            let series = site.get_series(&url).await.unwrap();
+            let filter = Filter::new(179..=179, None, Vec::new());
+            let chapters = site.get_chapters(&series, filter).await.unwrap();
+            let pages = site.get_pages(&chapters[0]).await.unwrap();
+
+            // The main image server is down.
+            for page in &pages {
+                transport
+                    .insert(&page.fallbacks[0], Response::new(200, "backup"));
+            }
+            site.mkdir(&chapters).unwrap();
+            site.download(&pages).await.unwrap();
+
+            for page in &pages {
+                let path = page.path(&site.output);
+                assert_eq!(std::fs::read(&path).unwrap(), b"backup");
+            }
+        });
+    }
+}
+
+// }}}
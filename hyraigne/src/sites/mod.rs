@@ -3,33 +3,63 @@
 use crate::Options;
 use url::Url;
 
+#[cfg(feature = "async")]
+mod async_engine;
+mod download;
+mod engine;
+mod foolslide;
+mod generic;
+mod hosts;
+mod madara;
 mod mangadex;
 mod mangakakalot;
+mod manganato;
+mod overrides;
+mod registry;
+mod scraper;
 mod traits;
 mod webtoons;
-mod webtoonscan;
 
+pub use generic::SiteDefinition;
+pub use madara::MadaraQuirks;
+pub use overrides::SelectorOverrides;
+pub use registry::{
+    SiteFactory,
+    SiteRegistry,
+    UrlMatcher,
+};
+#[cfg(feature = "async")]
+pub use traits::AsyncSite;
 pub use traits::Site;
 
+use foolslide::Site as FoolSlide;
+use madara::Site as Madara;
 use mangadex::Site as MangaDex;
 use mangakakalot::Site as MangaKakalot;
+use manganato::Site as Manganato;
 use webtoons::Site as Webtoons;
-use webtoonscan::Site as WebtoonScan;
 
 /// Return a web spider adapted to the given URL.
 ///
 /// If the given URL is not supported, `None` is returned.
+///
+/// This only considers the built-in web spiders, use a `SiteRegistry` to add
+/// your own.
 #[must_use]
 pub fn get_spider_for(url: &Url, opts: Options) -> Option<Box<dyn Site>> {
-    url.host_str().and_then(|hostname| {
-        let spider: Option<Box<dyn Site>> = match hostname {
-            "mangadex.org" => Some(Box::new(MangaDex::new(opts))),
-            "mangakakalot.com" => Some(Box::new(MangaKakalot::new(opts))),
-            "www.webtoons.com" => Some(Box::new(Webtoons::new(opts))),
-            "webtoonscan.com" => Some(Box::new(WebtoonScan::new(opts))),
-            _ => None,
-        };
+    SiteRegistry::default().get_spider_for(url, opts)
+}
 
-        spider
-    })
+/// Return the web spider for the website called `name` (e.g. `mangadex`).
+///
+/// If there is no website with this name, `None` is returned.
+#[must_use]
+pub fn get_spider_by_name(name: &str, opts: Options) -> Option<Box<dyn Site>> {
+    SiteRegistry::default().get_spider_by_name(name, opts)
+}
+
+/// Return a web spider for every supported website.
+#[must_use]
+pub fn get_all_spiders(opts: &Options) -> Vec<Box<dyn Site>> {
+    SiteRegistry::default().get_all_spiders(opts)
 }
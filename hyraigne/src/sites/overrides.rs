@@ -0,0 +1,207 @@
+//! User-defined CSS selectors, overriding those of the built-in web spiders.
+
+use super::{
+    foolslide,
+    madara,
+    mangakakalot,
+    manganato,
+    webtoons,
+};
+use crate::{
+    Error,
+    Result,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+
+/// CSS selectors overriding those of the built-in web spiders.
+///
+/// Selectors are validated as soon as they are set, so a typo is reported
+/// before any request is made.
+///
+/// # Example
+///
+/// ```toml
+/// [webtoons]
+/// chapter = "#_listUl li"
+/// page_url = "#_imageList img"
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SelectorOverrides {
+    /// Selectors, indexed by web spider then by selector name.
+    selectors: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl SelectorOverrides {
+    /// Initialize an empty set of overrides.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse and validate a set of overrides.
+    pub fn from_toml(overrides: &str) -> Result<Self> {
+        let raw: BTreeMap<String, BTreeMap<String, String>> =
+            toml::from_str(overrides).map_err(|err| {
+                Error::Config(format!("invalid selector overrides: {}", err))
+            })?;
+
+        let mut overrides = Self::new();
+        for (site, selectors) in &raw {
+            for (name, selector) in selectors {
+                overrides.set(site, name, selector)?;
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// Load a set of overrides from the TOML file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let overrides = fs::read_to_string(path).map_err(|err| {
+            Error::Filesystem {
+                operation: "read",
+                target: path.to_path_buf(),
+                source: err,
+            }
+        })?;
+
+        Self::from_toml(&overrides).map_err(|err| {
+            match err {
+                Error::Config(msg) => {
+                    Error::Config(format!("{}: {}", path.display(), msg))
+                },
+                err => err,
+            }
+        })
+    }
+
+    /// Override the selector `name` of the web spider `site`.
+    pub fn set(
+        &mut self,
+        site: &str,
+        name: &str,
+        selector: &str,
+    ) -> Result<()> {
+        let defaults = defaults_for(site).ok_or_else(|| {
+            Error::Config(format!("no overridable selector for {}", site))
+        })?;
+        if !defaults.iter().any(|&(default, _)| default == name) {
+            return Err(Error::Config(format!(
+                "unknown selector `{}` for {} (expected one of: {})",
+                name,
+                site,
+                defaults
+                    .iter()
+                    .map(|&(name, _)| name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+        kuchiki::Selectors::compile(selector).map_err(|()| {
+            Error::Config(format!(
+                "invalid {} {} selector `{}`",
+                site, name, selector
+            ))
+        })?;
+
+        self.selectors
+            .entry(site.to_owned())
+            .or_default()
+            .insert(name.to_owned(), selector.to_owned());
+
+        Ok(())
+    }
+
+    /// Return the selector `name` of the web spider `site`, if overridden.
+    #[must_use]
+    pub fn get(&self, site: &str, name: &str) -> Option<&str> {
+        self.selectors
+            .get(site)
+            .and_then(|selectors| selectors.get(name))
+            .map(String::as_str)
+    }
+
+    /// Compile the selector `name` of the web spider `site`.
+    ///
+    /// Fall back on the default from `defaults` when not overridden.
+    #[allow(clippy::expect_used)] // Overrides are validated, defaults valid.
+    pub(super) fn compile(
+        &self,
+        site: &str,
+        name: &str,
+        defaults: &[(&str, &str)],
+    ) -> kuchiki::Selectors {
+        let selector = self.get(site, name).unwrap_or_else(|| {
+            defaults
+                .iter()
+                .find(|&&(default, _)| default == name)
+                .map(|&(_, selector)| selector)
+                .expect("unknown selector")
+        });
+
+        kuchiki::Selectors::compile(selector).expect("invalid selector")
+    }
+}
+
+/// Return the default selectors of the built-in web spider `site`.
+fn defaults_for(site: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match site {
+        "foolslide" => Some(&foolslide::DEFAULT_SELECTORS),
+        "mangakakalot" => Some(&mangakakalot::DEFAULT_SELECTORS),
+        "manganato" => Some(&manganato::DEFAULT_SELECTORS),
+        "webtoons" => Some(&webtoons::DEFAULT_SELECTORS),
+        "madara" => Some(&madara::DEFAULT_SELECTORS),
+        _ if madara::SITES.iter().any(|config| config.name == site) => {
+            Some(&madara::DEFAULT_SELECTORS)
+        },
+        _ => None,
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let overrides = SelectorOverrides::from_toml(
+            r##"
+            [webtoons]
+            chapter = "#_listUl > li"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(overrides.get("webtoons", "chapter"), Some("#_listUl > li"));
+        assert_eq!(overrides.get("webtoons", "page_url"), None);
+    }
+
+    #[test]
+    fn test_invalid_selector() {
+        let err = SelectorOverrides::new()
+            .set("webtoons", "chapter", "li[")
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid configuration: invalid webtoons chapter selector `li[`"
+        );
+    }
+
+    #[test]
+    fn test_unknown_selector() {
+        let mut overrides = SelectorOverrides::new();
+
+        assert!(overrides.set("webtoons", "chapters", "li").is_err());
+        assert!(overrides.set("mangadex", "chapter", "li").is_err());
+    }
+}
+
+// }}}
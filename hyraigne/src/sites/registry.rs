@@ -0,0 +1,595 @@
+//! A registry of web spiders, to pick the right one for a given URL.
+
+#[cfg(feature = "async")]
+use super::{
+    async_engine::AsyncEngine,
+    AsyncSite,
+};
+use super::{
+    engine::Engine,
+    generic,
+    hosts,
+    madara,
+    scraper::Scraper,
+    FoolSlide,
+    Madara,
+    MadaraQuirks,
+    MangaDex,
+    MangaKakalot,
+    Manganato,
+    Site,
+    SiteDefinition,
+    Webtoons,
+};
+use crate::Options;
+use std::{
+    rc::Rc,
+    sync::Arc,
+};
+use url::Url;
+
+/// A function that instanciates a web spider with the given options.
+pub type SiteFactory = Box<dyn Fn(Options) -> Box<dyn Site>>;
+
+/// A function that instanciates an async web spider with the given options.
+#[cfg(feature = "async")]
+type AsyncSiteFactory = Box<dyn Fn(Options) -> Box<dyn AsyncSite>>;
+
+/// Describe which URLs a web spider is able to handle.
+pub enum UrlMatcher {
+    /// Match URLs on any of the given hostnames.
+    ///
+    /// The `www.` and mobile (`m.`) variants of each hostname match as well.
+    Hosts(Vec<String>),
+
+    /// Match URLs accepted by the predicate.
+    Predicate(Box<dyn Fn(&Url) -> bool>),
+}
+
+impl UrlMatcher {
+    /// Match URLs on the given hostname, or any of its aliases.
+    ///
+    /// The `www.` and mobile (`m.`) variants of each hostname match as well.
+    #[must_use]
+    pub fn hosts(hostnames: &[&str]) -> Self {
+        Self::Hosts(
+            hostnames
+                .iter()
+                .map(|hostname| hostname.to_ascii_lowercase())
+                .collect(),
+        )
+    }
+
+    /// Match URLs accepted by the predicate.
+    pub fn predicate<F>(predicate: F) -> Self
+    where
+        F: Fn(&Url) -> bool + 'static,
+    {
+        Self::Predicate(Box::new(predicate))
+    }
+
+    /// Test if `url` is matched.
+    #[must_use]
+    pub fn matches(&self, url: &Url) -> bool {
+        match *self {
+            Self::Hosts(ref hostnames) => {
+                url.host_str().map_or(false, |hostname| {
+                    hostnames
+                        .iter()
+                        .any(|candidate| hosts::same(candidate, hostname))
+                })
+            },
+            Self::Predicate(ref predicate) => predicate(url),
+        }
+    }
+}
+
+/// A registered web spider.
+struct Entry {
+    /// Name of the website.
+    name: String,
+    /// URLs supported by the web spider.
+    matcher: UrlMatcher,
+    /// Web spider constructor.
+    factory: SiteFactory,
+    /// Async web spider constructor, if available.
+    #[cfg(feature = "async")]
+    async_factory: Option<AsyncSiteFactory>,
+}
+
+/// A set of web spiders, indexed by name and supported URLs.
+///
+/// The default registry contains every built-in web spider, custom ones can be
+/// added with `SiteRegistry::register`.
+pub struct SiteRegistry {
+    entries: Vec<Entry>,
+}
+
+impl SiteRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Register a web spider for the website called `name`.
+    ///
+    /// Web spiders registered last take precedence, this allows to override
+    /// a built-in web spider.
+    pub fn register<F>(&mut self, name: &str, matcher: UrlMatcher, factory: F)
+    where
+        F: Fn(Options) -> Box<dyn Site> + 'static,
+    {
+        self.entries.push(Entry {
+            name: name.to_owned(),
+            matcher,
+            factory: Box::new(factory),
+            #[cfg(feature = "async")]
+            async_factory: None,
+        });
+    }
+
+    /// Register the generic web spider for the given site definition.
+    pub fn register_definition(&mut self, definition: SiteDefinition) {
+        let name = definition.name().to_owned();
+        let hosts = definition
+            .hosts()
+            .iter()
+            .map(|host| host.to_ascii_lowercase())
+            .collect();
+        let definition = Arc::new(definition);
+
+        self.register_scraper(&name, UrlMatcher::Hosts(hosts), move |_| {
+            generic::Site::new(Arc::clone(&definition))
+        });
+    }
+
+    /// Register the `FoOlSlide` web spider for the reader served at `host`.
+    ///
+    /// The website is named after its hostname, but the selector overrides
+    /// are shared by every `FoOlSlide` reader (under `foolslide`).
+    pub fn register_foolslide(&mut self, host: &str) {
+        let host = host.to_ascii_lowercase();
+
+        self.register_scraper(
+            &host,
+            UrlMatcher::hosts(&[&host]),
+            FoolSlide::new,
+        );
+    }
+
+    /// Register the Madara website called `name`, served at `base_url`.
+    ///
+    /// `quirks` describes how its URLs depart from the default Madara layout.
+    /// The selector overrides are shared by every Madara website registered
+    /// this way (under `madara`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hyraigne::{MadaraQuirks, SiteRegistry};
+    /// use url::Url;
+    ///
+    /// let mut registry = SiteRegistry::default();
+    /// let base_url = Url::parse("https://manhwa.example.com/").unwrap();
+    /// registry.register_madara(
+    ///     "manhwa-example",
+    ///     &base_url,
+    ///     MadaraQuirks::new().with_series_path("manhwa"),
+    /// );
+    /// ```
+    pub fn register_madara(
+        &mut self,
+        name: &str,
+        base_url: &Url,
+        quirks: MadaraQuirks,
+    ) {
+        self.add_madara(madara::Config::custom(name, base_url, quirks));
+    }
+
+    /// Register the Madara website described by `config`.
+    fn add_madara(&mut self, config: madara::Config) {
+        let config = Arc::new(config);
+
+        self.register_scraper(
+            &config.name.clone(),
+            UrlMatcher::hosts(&[&config.host()]),
+            move |opts| Madara::new(Arc::clone(&config), opts),
+        );
+    }
+
+    /// Register a web spider built on top of the scraper returned by `new`.
+    ///
+    /// Such web spiders are available through both the blocking and the async
+    /// APIs.
+    fn register_scraper<S, F>(
+        &mut self,
+        name: &str,
+        matcher: UrlMatcher,
+        new: F,
+    ) where
+        S: Scraper + 'static,
+        F: Fn(&Options) -> S + 'static,
+    {
+        let new = Rc::new(new);
+        #[cfg(feature = "async")]
+        let async_factory = {
+            let new = Rc::clone(&new);
+            Box::new(move |opts: Options| -> Box<dyn AsyncSite> {
+                Box::new(AsyncEngine::new(new(&opts), opts))
+            })
+        };
+
+        self.entries.push(Entry {
+            name: name.to_owned(),
+            matcher,
+            factory: Box::new(move |opts: Options| -> Box<dyn Site> {
+                Box::new(Engine::new(new(&opts), opts))
+            }),
+            #[cfg(feature = "async")]
+            async_factory: Some(async_factory),
+        });
+    }
+
+    /// Return the names of the registered websites.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.latest_entries().map(|entry| entry.name.as_str())
+    }
+
+    /// Return the name of the website that supports `url`, if any.
+    #[must_use]
+    pub fn name_for(&self, url: &Url) -> Option<&str> {
+        self.find(|entry| entry.matcher.matches(url))
+            .map(|entry| entry.name.as_str())
+    }
+
+    /// Return a web spider adapted to the given URL.
+    ///
+    /// If the given URL is not supported, `None` is returned.
+    #[must_use]
+    pub fn get_spider_for(
+        &self,
+        url: &Url,
+        opts: Options,
+    ) -> Option<Box<dyn Site>> {
+        // URLs on a remapped base are handled by the original web spider.
+        let original = opts.base_urls.restore(url);
+
+        self.find(|entry| {
+            entry.matcher.matches(url) || entry.matcher.matches(&original)
+        })
+        .map(|entry| (entry.factory)(opts.for_site(&entry.name)))
+    }
+
+    /// Return the web spider for the website called `name`.
+    ///
+    /// If there is no website with this name, `None` is returned.
+    #[must_use]
+    pub fn get_spider_by_name(
+        &self,
+        name: &str,
+        opts: Options,
+    ) -> Option<Box<dyn Site>> {
+        self.find(|entry| entry.name == name)
+            .map(|entry| (entry.factory)(opts.for_site(name)))
+    }
+
+    /// Return a web spider for every registered website.
+    ///
+    /// Overridden web spiders are left out, in favor of their replacement.
+    #[must_use]
+    pub fn get_all_spiders(&self, opts: &Options) -> Vec<Box<dyn Site>> {
+        self.latest_entries()
+            .map(|entry| (entry.factory)(opts.clone().for_site(&entry.name)))
+            .collect()
+    }
+
+    /// Return an async web spider adapted to the given URL.
+    ///
+    /// Only the web spiders built on a scraper (the built-in ones, and those
+    /// registered from a site definition or as a Madara or `FoOlSlide`
+    /// website) are available: if the web spider adapted to the given URL is
+    /// only available through the blocking API, `None` is returned.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn get_async_spider_for(
+        &self,
+        url: &Url,
+        opts: Options,
+    ) -> Option<Box<dyn AsyncSite>> {
+        // URLs on a remapped base are handled by the original web spider.
+        let original = opts.base_urls.restore(url);
+
+        self.find_async(|entry| {
+            entry.matcher.matches(url) || entry.matcher.matches(&original)
+        })
+        .map(|(entry, factory)| factory(opts.for_site(&entry.name)))
+    }
+
+    /// Return the async web spider for the website called `name`.
+    ///
+    /// If there is no such website, or if its web spider is only available
+    /// through the blocking API, `None` is returned.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn get_async_spider_by_name(
+        &self,
+        name: &str,
+        opts: Options,
+    ) -> Option<Box<dyn AsyncSite>> {
+        self.find_async(|entry| entry.name == name)
+            .map(|(_, factory)| factory(opts.for_site(name)))
+    }
+
+    /// Return the most recently registered entry matching `predicate`.
+    fn find<P>(&self, predicate: P) -> Option<&Entry>
+    where
+        P: Fn(&Entry) -> bool,
+    {
+        self.entries.iter().rev().find(|entry| predicate(entry))
+    }
+
+    /// Return the most recently registered entry of each website.
+    fn latest_entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(move |(idx, entry)| {
+                let overridden = self.entries[idx + 1..]
+                    .iter()
+                    .any(|later| later.name == entry.name);
+
+                if overridden {
+                    None
+                } else {
+                    Some(entry)
+                }
+            })
+    }
+
+    /// Return the most recently registered entry matching `predicate`, if
+    /// it's available through the async API.
+    #[cfg(feature = "async")]
+    fn find_async<P>(&self, predicate: P) -> Option<(&Entry, &AsyncSiteFactory)>
+    where
+        P: Fn(&Entry) -> bool,
+    {
+        self.find(predicate).and_then(|entry| {
+            entry.async_factory.as_ref().map(|factory| (entry, factory))
+        })
+    }
+}
+
+impl Default for SiteRegistry {
+    /// Create a registry with every built-in web spider.
+    fn default() -> Self {
+        let mut registry = Self::new();
+
+        registry.register_scraper(
+            "mangadex",
+            UrlMatcher::hosts(&["mangadex.org"]),
+            |_| MangaDex,
+        );
+        registry.register_scraper(
+            "mangakakalot",
+            UrlMatcher::hosts(&["mangakakalot.com"]),
+            MangaKakalot::new,
+        );
+        registry.register_scraper(
+            "manganato",
+            // Including its former domain, Manganelo.
+            UrlMatcher::hosts(&[
+                "manganato.com",
+                "readmanganato.com",
+                "chapmanganato.com",
+                "manganelo.com",
+            ]),
+            Manganato::new,
+        );
+        registry.register_scraper(
+            "webtoons",
+            UrlMatcher::hosts(&["www.webtoons.com"]),
+            Webtoons::new,
+        );
+        for &config in &madara::SITES {
+            registry.add_madara(config.clone());
+        }
+
+        registry
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing,
+        LinkKind,
+        MemoryTransport,
+    };
+
+    #[test]
+    fn test_builtin_sites() {
+        let registry = SiteRegistry::default();
+        let url = Url::parse("https://mangakakalot.com/read-lu8jl158504848312")
+            .unwrap();
+
+        assert_eq!(registry.name_for(&url), Some("mangakakalot"));
+        assert_eq!(registry.names().count(), 5);
+    }
+
+    #[test]
+    fn test_unsupported_url() {
+        let registry = SiteRegistry::default();
+        let url = Url::parse("https://example.com/manga/42").unwrap();
+
+        assert_eq!(registry.name_for(&url), None);
+    }
+
+    #[test]
+    fn test_host_aliases() {
+        let mut registry = SiteRegistry::new();
+        registry.register(
+            "example",
+            UrlMatcher::hosts(&["example.com", "WWW.Example.com"]),
+            |opts| Box::new(Engine::new(MangaKakalot::new(&opts), opts)),
+        );
+        let url = Url::parse("https://www.example.com/manga/42").unwrap();
+
+        assert_eq!(registry.name_for(&url), Some("example"));
+    }
+
+    #[test]
+    fn test_builtin_host_aliases() {
+        let registry = SiteRegistry::default();
+        let name_for = |url| {
+            registry
+                .name_for(&Url::parse(url).unwrap())
+                .map(str::to_owned)
+        };
+
+        for url in &[
+            "https://webtoons.com/en/top",
+            "https://m.webtoons.com/en/top",
+            "https://www.webtoons.com/en/top",
+        ] {
+            assert_eq!(name_for(url).as_deref(), Some("webtoons"), "{}", url);
+        }
+        for url in &[
+            "https://manganelo.com/manga/xn921310",
+            "https://readmanganato.com/manga-xn921310",
+            "https://chapmanganato.com/manga-xn921310/chapter-1",
+        ] {
+            assert_eq!(name_for(url).as_deref(), Some("manganato"), "{}", url);
+        }
+        assert_eq!(
+            name_for("https://www.mangadex.org/title/642").as_deref(),
+            Some("mangadex")
+        );
+        assert_eq!(name_for("https://api.mangadex.org/v2/manga/642"), None);
+    }
+
+    #[test]
+    fn test_foolslide() {
+        let mut registry = SiteRegistry::default();
+        registry.register_foolslide("Reader.Example.com");
+        let url = Url::parse(
+            "https://www.reader.example.com/slide/series/moonlit-gardens/",
+        )
+        .unwrap();
+        let opts = Options::new(0, 0, std::env::temp_dir());
+
+        assert_eq!(registry.name_for(&url), Some("reader.example.com"));
+        assert!(registry.get_spider_for(&url, opts).is_some());
+    }
+
+    #[test]
+    fn test_madara() {
+        let mut registry = SiteRegistry::default();
+        registry.register_madara(
+            "manhwa-example",
+            &Url::parse("https://manhwa.example.com/").unwrap(),
+            MadaraQuirks::new()
+                .with_series_path("manhwa")
+                .with_chapter_prefix(""),
+        );
+        let url = Url::parse(
+            "https://manhwa.example.com/manhwa/legend-of-the-northern-blade/",
+        )
+        .unwrap();
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, url.as_str(), "webtoonscan.com/series.html");
+        let opts = testing::options(&transport, "registry-madara");
+
+        let spider = registry.get_spider_for(&url, opts).unwrap();
+        let series = spider.get_series(&url).unwrap();
+
+        assert_eq!(registry.name_for(&url), Some("manhwa-example"));
+        assert_eq!(
+            spider.classify(&url.join("42/").unwrap()),
+            LinkKind::Chapter
+        );
+        assert_eq!(series.title, "Legend Of The Northern Blade");
+    }
+
+    #[test]
+    fn test_remapped_base_url() {
+        let registry = SiteRegistry::default();
+        let opts = Options::new(0, 0, std::env::temp_dir()).with_base_url(
+            &Url::parse("https://mangakakalot.com").unwrap(),
+            &Url::parse("http://127.0.0.1:8080/mangakakalot/").unwrap(),
+        );
+        let url =
+            Url::parse("http://127.0.0.1:8080/mangakakalot/manga/42").unwrap();
+
+        assert!(registry.get_spider_for(&url, opts).is_some());
+    }
+
+    #[test]
+    fn test_predicate_override() {
+        let mut registry = SiteRegistry::default();
+        registry.register(
+            "private",
+            UrlMatcher::predicate(|url| url.path().starts_with("/private/")),
+            |opts| Box::new(Engine::new(MangaKakalot::new(&opts), opts)),
+        );
+        let private =
+            Url::parse("https://mangakakalot.com/private/42").unwrap();
+        let public = Url::parse("https://mangakakalot.com/manga/42").unwrap();
+
+        assert_eq!(registry.name_for(&private), Some("private"));
+        assert_eq!(registry.name_for(&public), Some("mangakakalot"));
+    }
+
+    #[test]
+    fn test_all_spiders_overridden() {
+        let mut registry = SiteRegistry::default();
+        registry.register(
+            "mangakakalot",
+            UrlMatcher::hosts(&["mangakakalot.com"]),
+            |opts| Box::new(Engine::new(MangaKakalot::new(&opts), opts)),
+        );
+        let opts = Options::new(0, 0, std::env::temp_dir());
+
+        let names = registry.names().collect::<Vec<_>>();
+
+        assert_eq!(registry.get_all_spiders(&opts).len(), 5);
+        assert_eq!(names.len(), 5);
+        assert_eq!(names.last(), Some(&"mangakakalot"));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_spiders() {
+        let mut registry = SiteRegistry::default();
+        registry.register(
+            "private",
+            UrlMatcher::predicate(|url| url.path().starts_with("/private/")),
+            |opts| Box::new(Engine::new(MangaKakalot::new(&opts), opts)),
+        );
+        let opts = Options::new(0, 0, std::env::temp_dir());
+        let private =
+            Url::parse("https://mangakakalot.com/private/42").unwrap();
+        let public = Url::parse("https://mangakakalot.com/manga/42").unwrap();
+
+        // Blocking-only web spiders aren't replaced by the ones they override.
+        assert!(registry
+            .get_async_spider_for(&private, opts.clone())
+            .is_none());
+        assert!(registry
+            .get_async_spider_for(&public, opts.clone())
+            .is_some());
+        assert!(registry
+            .get_async_spider_by_name("private", opts.clone())
+            .is_none());
+        assert!(registry
+            .get_async_spider_by_name("webtoons", opts)
+            .is_some());
+    }
+}
+
+// }}}
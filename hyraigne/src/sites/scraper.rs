@@ -0,0 +1,367 @@
+//! What a web spider knows about a website, independently of the HTTP client.
+//!
+//! A `Scraper` only builds URLs and extracts data from the fetched documents:
+//! the requests themselves are made by an engine (blocking or async), so that
+//! a single implementation serves both APIs.
+
+use crate::{
+    spiders::Request,
+    types::Events,
+    Chapter,
+    Error,
+    Filter,
+    LinkKind,
+    Listing,
+    Options,
+    Page,
+    Result,
+    SelectorCheck,
+    Series,
+    SeriesSummary,
+};
+use cookie_store::CookieStore;
+use kuchiki::traits::*;
+use serde::de::DeserializeOwned;
+use url::Url;
+
+/// Upper bound on the number of chapter list pages, to avoid endless loops.
+pub(super) const MAX_CHAPTER_LIST_PAGES: u16 = 1000;
+
+/// Format of the documents served by a website.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Format {
+    /// HTML pages.
+    Html,
+    /// JSON API responses.
+    Json,
+}
+
+impl Format {
+    /// Value of the `Accept` header to request this format.
+    pub(crate) const fn accept(self) -> &'static str {
+        match self {
+            Self::Html => "text/html",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+/// A document fetched from a website.
+pub(crate) enum Document {
+    /// A parsed HTML page.
+    Html(kuchiki::NodeRef),
+    /// A JSON response.
+    Json(serde_json::Value),
+}
+
+impl Document {
+    /// Parse the `body` served at `url`.
+    pub(crate) fn parse(
+        format: Format,
+        url: &Url,
+        body: Vec<u8>,
+    ) -> Result<Self> {
+        match format {
+            Format::Html => {
+                let html = String::from_utf8(body).map_err(|err| {
+                    log::error!(
+                        "failed to read HTML from {}: {}",
+                        url.as_str(),
+                        err
+                    );
+                    Error::Payload {
+                        url: url.to_string(),
+                        source: Box::new(err),
+                    }
+                })?;
+
+                Ok(Self::Html(kuchiki::parse_html().one(html)))
+            },
+            Format::Json => {
+                serde_json::from_slice(&body)
+                    .map(Self::Json)
+                    .map_err(|err| {
+                        log::error!(
+                            "failed to read JSON from {}: {}",
+                            url.as_str(),
+                            err
+                        );
+                        Error::Payload {
+                            url: url.to_string(),
+                            source: Box::new(err),
+                        }
+                    })
+            },
+        }
+    }
+
+    /// Return the HTML page.
+    pub(crate) fn html(&self) -> Result<&kuchiki::NodeRef> {
+        match *self {
+            Self::Html(ref html) => Ok(html),
+            Self::Json(_) => {
+                Err(Error::Scraping("expected an HTML document".to_owned()))
+            },
+        }
+    }
+
+    /// Deserialize the JSON response.
+    pub(crate) fn json<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        match *self {
+            Self::Json(ref value) => {
+                T::deserialize(value).map_err(|err| {
+                    Error::Scraping(format!(
+                        "unexpected JSON response: {}",
+                        err
+                    ))
+                })
+            },
+            Self::Html(_) => {
+                Err(Error::Scraping("expected a JSON document".to_owned()))
+            },
+        }
+    }
+}
+
+/// What a chapter (or page) link resolves to.
+pub(crate) struct ChapterLink {
+    /// URL of the series the chapter belongs to.
+    pub(crate) series: Url,
+    /// Chapter ID.
+    pub(crate) id: f64,
+    /// Chapter volume, if any.
+    pub(crate) volume: Option<String>,
+    /// Chapter URL, as listed in the series.
+    pub(crate) url: Url,
+}
+
+impl ChapterLink {
+    /// Build the chapter of `series` pointed by the link.
+    pub(crate) fn into_chapter(self, series: &Series) -> Chapter<'_> {
+        Chapter {
+            id: self.id,
+            series,
+            volume: self.volume,
+            url: self.url,
+        }
+    }
+}
+
+/// Scraping logic of a website, shared by the blocking and async engines.
+pub(crate) trait Scraper: Send + Sync {
+    /// Format of the documents served by the website.
+    fn format(&self) -> Format {
+        Format::Html
+    }
+
+    /// Cookies required by the website, if any.
+    fn cookies(&self, _options: &Options) -> Option<CookieStore> {
+        None
+    }
+
+    /// URL of the search results for `query`.
+    fn search_url(&self, _query: &str) -> Result<Url> {
+        Err(Error::Unsupported("search"))
+    }
+
+    /// Extract the search results.
+    fn scrape_search(
+        &self,
+        _document: &Document,
+    ) -> Result<Vec<SeriesSummary>> {
+        Err(Error::Unsupported("search"))
+    }
+
+    /// URL of the `page`-th page (starting from 1) of the given listing.
+    ///
+    /// `None` is returned when there is no such page.
+    fn listing_url(
+        &self,
+        _listing: Listing,
+        _page: u16,
+    ) -> Result<Option<Url>> {
+        Err(Error::Unsupported("browsing"))
+    }
+
+    /// Extract the series of a listing.
+    fn scrape_listing(
+        &self,
+        _listing: Listing,
+        _document: &Document,
+    ) -> Result<Vec<SeriesSummary>> {
+        Err(Error::Unsupported("browsing"))
+    }
+
+    /// Run every selector (and regexp) against `html`.
+    fn check_selectors(&self, _html: &str) -> Result<Vec<SelectorCheck>> {
+        Err(Error::Unsupported("selector checks"))
+    }
+
+    /// Rewrite `url` into the canonical form expected by the scraper (e.g.
+    /// from a mobile domain or a mirror).
+    fn canonical_url(&self, url: &Url) -> Url {
+        url.clone()
+    }
+
+    /// Tell if `url` links to a series, a chapter or a page.
+    fn classify(&self, _url: &Url) -> LinkKind {
+        LinkKind::Series
+    }
+
+    /// Resolve the chapter (or page) link `url` without fetching anything.
+    ///
+    /// `None` is returned when the chapter must be scraped, see
+    /// `scrape_chapter`.
+    fn parse_chapter_link(&self, _url: &Url) -> Option<ChapterLink> {
+        None
+    }
+
+    /// URL of the document describing the chapter (or page) link `url`.
+    fn chapter_url(&self, url: &Url) -> Result<Url> {
+        Ok(url.clone())
+    }
+
+    /// Extract the chapter served at `url`.
+    fn scrape_chapter(
+        &self,
+        _url: &Url,
+        _document: &Document,
+    ) -> Result<ChapterLink> {
+        Err(Error::Unsupported("chapter links"))
+    }
+
+    /// URL of the document describing the series at `url`.
+    fn series_url(&self, url: &Url) -> Result<Url> {
+        Ok(url.clone())
+    }
+
+    /// Extract the series served at `url`.
+    fn scrape_series(&self, url: &Url, document: &Document) -> Result<Series>;
+
+    /// URL of the `index`-th page (starting from 0) of the chapters list.
+    ///
+    /// `None` is returned when there is no more page to fetch. The engines
+    /// also stop on the first page that doesn't bring new chapters.
+    fn chapters_url(
+        &self,
+        series: &Series,
+        _filter: &Filter,
+        index: u16,
+    ) -> Option<Url> {
+        if index == 0 {
+            Some(series.url.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Extract the chapters listed at `url`.
+    fn scrape_chapters<'a>(
+        &self,
+        url: &Url,
+        series: &'a Series,
+        filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>>;
+
+    /// Request loading the chapters missing from the page at `url`, if any
+    /// (e.g. when they are loaded by JavaScript).
+    ///
+    /// Only used when `scrape_chapters` found nothing on the page. The
+    /// response is handed to `scrape_chapters` as well.
+    fn chapters_request(
+        &self,
+        _url: &Url,
+        _document: &Document,
+    ) -> Result<Option<Request>> {
+        Ok(None)
+    }
+
+    /// Test if `chapter` is selected by `filter`.
+    fn keep_chapter(&self, chapter: &Chapter<'_>, filter: &Filter) -> bool {
+        let start = f64::from(*filter.range.start());
+        let end = f64::from(*filter.range.end());
+
+        (start..=end).contains(&chapter.id)
+    }
+
+    /// Extract the pages of `chapter`.
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>>;
+
+    /// Referer to send when downloading `page`.
+    fn referer<'a>(&'a self, page: &'a Page<'_>) -> &'a Url {
+        &page.chapter.url
+    }
+}
+
+/// Test if the truncated ID of `chapter` is selected by `filter`.
+///
+/// For the websites where the chapter ID is an episode number.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(super) fn keep_truncated_id(
+    chapter: &Chapter<'_>,
+    filter: &Filter,
+) -> bool {
+    // Chapter IDs are always positive and under u16::MAX.
+    filter.range.contains(&(chapter.id as u16))
+}
+
+/// Turn an error raised while scraping `what` from `url` into a scraping
+/// error.
+pub(super) fn scraping_error(what: &str, url: &Url, err: &Error) -> Error {
+    Error::Scraping(format!(
+        "failed to scrape {} from {}: {}",
+        what,
+        url.as_str(),
+        err
+    ))
+}
+
+/// Add the chapters of `page` that aren't in `chapters` yet.
+///
+/// Return `false` if the page didn't bring any new chapter.
+pub(super) fn merge_chapters<'a>(
+    chapters: &mut Vec<Chapter<'a>>,
+    page: Vec<Chapter<'a>>,
+) -> bool {
+    let count = chapters.len();
+
+    for chapter in page {
+        if !chapters.iter().any(|known| known.url == chapter.url) {
+            chapters.push(chapter);
+        }
+    }
+
+    chapters.len() > count
+}
+
+/// Keep the chapters selected by `filter`, sorted by ID, and report them.
+pub(super) fn select_chapters<'a, S>(
+    scraper: &S,
+    mut chapters: Vec<Chapter<'a>>,
+    filter: &Filter,
+    events: &Events,
+) -> Vec<Chapter<'a>>
+where
+    S: Scraper + ?Sized,
+{
+    log::debug!("found {} chapters", chapters.len());
+
+    // Trim the chapters list to keep only the requested chapters.
+    chapters.retain(|chapter| scraper.keep_chapter(chapter, filter));
+
+    #[allow(clippy::expect_used)] // Shouldn't have NaN & friends here…
+    chapters.sort_by(|a, b| a.partial_cmp(b).expect("abnormal float as ID"));
+    log::debug!("selected {} chapters", chapters.len());
+
+    events.chapters_listed(&chapters);
+
+    chapters
+}
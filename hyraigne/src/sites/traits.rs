@@ -1,15 +1,62 @@
 use crate::{
     Chapter,
+    Error,
     Filter,
+    LinkKind,
+    Listing,
     Page,
     Result,
+    SelectorCheck,
     Series,
+    SeriesSummary,
 };
 use url::Url;
 
 /// A website scraper.
 pub trait Site {
+    /// Search the website for series matching `query`.
+    ///
+    /// Not every website supports this, in which case `Error::Unsupported` is
+    /// returned.
+    fn search(&self, _query: &str) -> Result<Vec<SeriesSummary>> {
+        Err(Error::Unsupported("search"))
+    }
+
+    /// Fetch the `page`-th page (starting from 1) of the given listing.
+    ///
+    /// An empty list is returned when there is no more page to fetch.
+    ///
+    /// Not every website supports this, in which case `Error::Unsupported` is
+    /// returned.
+    fn browse(
+        &self,
+        _listing: Listing,
+        _page: u16,
+    ) -> Result<Vec<SeriesSummary>> {
+        Err(Error::Unsupported("browsing"))
+    }
+
+    /// Run every selector (and regexp) of the web spider against `html`.
+    ///
+    /// Useful to find out which part of the markup of a website changed.
+    ///
+    /// Not every website supports this (e.g. those relying on an API), in
+    /// which case `Error::Unsupported` is returned.
+    fn check_selectors(&self, _html: &str) -> Result<Vec<SelectorCheck>> {
+        Err(Error::Unsupported("selector checks"))
+    }
+
+    /// Tell if `url` links to a series, a chapter or a page.
+    ///
+    /// Web spiders that only handle series links always return
+    /// `LinkKind::Series`.
+    fn classify(&self, _url: &Url) -> LinkKind {
+        LinkKind::Series
+    }
+
     /// Fetch the series at `url`.
+    ///
+    /// For chapter (and page) links, this is the series they belong to.
     fn get_series(&self, url: &Url) -> Result<Series>;
 
     /// Fetch the chapters of `series` and filter them as specified.
@@ -19,6 +66,18 @@ pub trait Site {
         filter: Filter,
     ) -> Result<Vec<Chapter<'a>>>;
 
+    /// Fetch the chapter of `series` linked by `url` (a chapter or page link).
+    ///
+    /// Not every website supports this, in which case `Error::Unsupported` is
+    /// returned.
+    fn get_chapter<'a>(
+        &self,
+        _series: &'a Series,
+        _url: &Url,
+    ) -> Result<Chapter<'a>> {
+        Err(Error::Unsupported("chapter links"))
+    }
+
     /// Fetch the pages of the given chapter.
     fn get_pages<'a>(&self, chapter: &'a Chapter<'_>) -> Result<Vec<Page<'a>>>;
 
@@ -28,3 +87,84 @@ pub trait Site {
     /// Download the given pages.
     fn download(&self, pages: &[Page<'_>]) -> Result<()>;
 }
+
+/// An async website scraper, the counterpart of `Site` for the async API.
+///
+/// Available with the `async` feature.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncSite: Send + Sync {
+    /// Search the website for series matching `query`.
+    ///
+    /// Not every website supports this, in which case `Error::Unsupported` is
+    /// returned.
+    async fn search(&self, _query: &str) -> Result<Vec<SeriesSummary>> {
+        Err(Error::Unsupported("search"))
+    }
+
+    /// Fetch the `page`-th page (starting from 1) of the given listing.
+    ///
+    /// An empty list is returned when there is no more page to fetch.
+    ///
+    /// Not every website supports this, in which case `Error::Unsupported` is
+    /// returned.
+    async fn browse(
+        &self,
+        _listing: Listing,
+        _page: u16,
+    ) -> Result<Vec<SeriesSummary>> {
+        Err(Error::Unsupported("browsing"))
+    }
+
+    /// Run every selector (and regexp) of the web spider against `html`.
+    ///
+    /// Not every website supports this (e.g. those relying on an API), in
+    /// which case `Error::Unsupported` is returned.
+    fn check_selectors(&self, _html: &str) -> Result<Vec<SelectorCheck>> {
+        Err(Error::Unsupported("selector checks"))
+    }
+
+    /// Tell if `url` links to a series, a chapter or a page.
+    fn classify(&self, _url: &Url) -> LinkKind {
+        LinkKind::Series
+    }
+
+    /// Fetch the series at `url`.
+    ///
+    /// For chapter (and page) links, this is the series they belong to.
+    async fn get_series(&self, url: &Url) -> Result<Series>;
+
+    /// Fetch the chapters of `series` and filter them as specified.
+    async fn get_chapters<'a>(
+        &self,
+        series: &'a Series,
+        filter: Filter,
+    ) -> Result<Vec<Chapter<'a>>>;
+
+    /// Fetch the chapter of `series` linked by `url` (a chapter or page link).
+    ///
+    /// Not every website supports this, in which case `Error::Unsupported` is
+    /// returned.
+    async fn get_chapter<'a>(
+        &self,
+        _series: &'a Series,
+        _url: &Url,
+    ) -> Result<Chapter<'a>> {
+        Err(Error::Unsupported("chapter links"))
+    }
+
+    /// Fetch the pages of the given chapter.
+    async fn get_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+    ) -> Result<Vec<Page<'a>>>;
+
+    /// Create the required directory hierarchy to download the pages.
+    fn mkdir(&self, chapters: &[Chapter<'_>]) -> Result<()>;
+
+    /// Download the given pages.
+    ///
+    /// The images are fetched asynchronously, but written synchronously (they
+    /// are small enough).
+    async fn download(&self, pages: &[Page<'_>]) -> Result<()>;
+}
@@ -1,7 +1,4 @@
-use super::selectors::{
-    CHAPTER_SELECTOR,
-    CHAPTER_URL_SELECTOR,
-};
+use super::selectors::Selectors;
 use crate::{
     Chapter,
     Error,
@@ -16,8 +13,10 @@ use url::Url;
 pub(super) fn scrape_from_html<'a>(
     html: &kuchiki::NodeRef,
     series: &'a Series,
+    selectors: &Selectors,
 ) -> Result<Vec<Chapter<'a>>> {
-    CHAPTER_SELECTOR
+    selectors
+        .chapter
         .filter(html.descendants().elements())
         .map(|chapter| {
             let chapter = chapter.as_node();
@@ -26,7 +25,7 @@ pub(super) fn scrape_from_html<'a>(
                 id: f64::from(id_from_html(chapter)?),
                 series,
                 volume: None,
-                url: url_from_html(chapter)?,
+                url: url_from_html(chapter, selectors)?,
             })
         })
         .collect::<Result<Vec<_>>>()
@@ -50,8 +49,12 @@ pub(super) fn id_from_html(html: &kuchiki::NodeRef) -> Result<u16> {
 
 /// Extract the chapter URL.
 #[allow(clippy::filter_next)]
-fn url_from_html(html: &kuchiki::NodeRef) -> Result<Url> {
-    let element = CHAPTER_URL_SELECTOR
+fn url_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Url> {
+    let element = selectors
+        .chapter_url
         .filter(html.descendants().elements())
         .next()
         .ok_or_else(|| Error::Scraping("chapter link not found".to_owned()))?;
@@ -86,7 +89,9 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let chapters = scrape_from_html(&document, &series).unwrap();
+        let chapters =
+            scrape_from_html(&document, &series, &Selectors::default())
+                .unwrap();
 
         assert_eq!(chapters.len(), 10);
     }
@@ -0,0 +1,75 @@
+use super::{
+    search,
+    selectors::Selectors,
+};
+use crate::{
+    Listing,
+    Result,
+    SeriesSummary,
+};
+
+/// Scrape the series from the given listing.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    listing: Listing,
+    selectors: &Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    let selector = match listing {
+        Listing::Latest => &selectors.latest,
+        Listing::Popular => &selectors.popular,
+    };
+
+    search::scrape_cards(html, selector, selectors)
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+    use std::path::PathBuf;
+
+    fn load_document(filename: &str) -> kuchiki::NodeRef {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/webtoons.com");
+        path.push(filename);
+        let html = std::fs::read_to_string(&path).expect("test data");
+
+        kuchiki::parse_html().one(html)
+    }
+
+    #[test]
+    fn test_scraping_latest() {
+        let document = load_document("daily_schedule.html");
+
+        let results =
+            scrape_from_html(&document, Listing::Latest, &Selectors::default())
+                .unwrap();
+
+        // Only the series of the current day are listed.
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].title, "The God of High School");
+    }
+
+    #[test]
+    fn test_scraping_popular() {
+        let document = load_document("top.html");
+
+        let results = scrape_from_html(
+            &document,
+            Listing::Popular,
+            &Selectors::default(),
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].title, "Lore Olympus");
+        assert_eq!(
+            results[0].url.as_str(),
+            "https://www.webtoons.com/en/romance/lore-olympus/list?title_no=1320"
+        );
+    }
+}
+
+// }}}
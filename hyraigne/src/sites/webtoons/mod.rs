@@ -1,7 +1,10 @@
 mod chapter;
+mod listing;
 mod page;
+mod search;
 mod selectors;
 mod series;
 mod site;
 
+pub(super) use selectors::DEFAULTS as DEFAULT_SELECTORS;
 pub(crate) use site::Site;
@@ -1,4 +1,4 @@
-use super::selectors::PAGE_URL_SELECTOR;
+use super::selectors::Selectors;
 use crate::{
     Chapter,
     Error,
@@ -12,8 +12,10 @@ use url::Url;
 pub(super) fn scrape_from_html<'a>(
     html: &kuchiki::NodeRef,
     chapter: &'a Chapter<'_>,
+    selectors: &Selectors,
 ) -> Result<Vec<Page<'a>>> {
-    PAGE_URL_SELECTOR
+    selectors
+        .page_url
         .filter(html.descendants().elements())
         .enumerate()
         .map(|(idx, node)| {
@@ -31,7 +33,7 @@ pub(super) fn scrape_from_html<'a>(
                 id: (idx + 1) as u16,
                 chapter,
                 main: url,
-                fallback: None,
+                fallbacks: Vec::new(),
             })
         })
         .collect::<Result<Vec<_>>>()
@@ -66,7 +68,9 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let pages = scrape_from_html(&document, &chapter).unwrap();
+        let pages =
+            scrape_from_html(&document, &chapter, &Selectors::default())
+                .unwrap();
 
         assert_eq!(pages.len(), 32);
     }
@@ -0,0 +1,95 @@
+use super::selectors::Selectors;
+use crate::{
+    Error,
+    Result,
+    SeriesSummary,
+};
+use kuchiki::traits::*;
+use url::Url;
+
+/// Scrape the series listed in the search results.
+///
+/// Only the WEBTOON Originals are returned, Canvas series are ignored.
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    scrape_cards(html, &selectors.search_result, selectors)
+}
+
+/// Scrape the series cards matched by `selector`.
+pub(super) fn scrape_cards(
+    html: &kuchiki::NodeRef,
+    selector: &kuchiki::Selectors,
+    selectors: &Selectors,
+) -> Result<Vec<SeriesSummary>> {
+    selector
+        .filter(html.descendants().elements())
+        .map(|entry| {
+            Ok(SeriesSummary {
+                title: title_from_html(entry.as_node(), selectors)?,
+                url: url_from_element(&entry)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Extract series title from `<p class="subj">TITLE</p>`.
+#[allow(clippy::filter_next)]
+fn title_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<String> {
+    let raw_title = selectors
+        .search_title
+        .filter(html.descendants().elements())
+        .next()
+        .ok_or_else(|| Error::Scraping("series title not found".to_owned()))?
+        .text_contents();
+    let title = raw_title.trim();
+
+    if title.is_empty() {
+        return Err(Error::Scraping("series title is missing".to_owned()));
+    }
+    Ok(title.to_owned())
+}
+
+/// Extract the series URL.
+fn url_from_element(element: &kuchiki::ElementData) -> Result<Url> {
+    let attributes = element.attributes.borrow();
+    let url = attributes
+        .get("href")
+        .ok_or_else(|| Error::Scraping("series URL not found".to_owned()))?;
+
+    Url::parse(url).map_err(|err| {
+        Error::Scraping(format!("invalid series URL `{}`: {}", url, err))
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_scraping() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("testdata/webtoons.com/search.html");
+        let html = std::fs::read_to_string(&path).expect("test data");
+        let document = kuchiki::parse_html().one(html);
+
+        let results =
+            scrape_from_html(&document, &Selectors::default()).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Tower of God");
+        assert_eq!(
+            results[0].url.as_str(),
+            "https://www.webtoons.com/episodeList?titleNo=95"
+        );
+    }
+}
+
+// }}}
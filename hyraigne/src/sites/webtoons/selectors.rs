@@ -1,42 +1,121 @@
 //! CSS selectors to scrape `https://www.webtoons.com`.
 
-use once_cell::sync::Lazy;
-
-/// Select `<meta property="og:title" content="TITLE" />`
-#[allow(clippy::expect_used)]
-pub(super) static SERIES_TITLE_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile("meta[property=\"og:title\"]")
-            .expect("invalid series title selector")
-    });
-
-/// Select `<meta property="og:url" content="URL" />`
-#[allow(clippy::expect_used)]
-pub(super) static SERIES_URL_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile("meta[property=\"og:url\"]")
-            .expect("invalid series URL selector")
-    });
-
-/// Select chapter entries in the chapter list.
-#[allow(clippy::expect_used)]
-pub(super) static CHAPTER_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile("#_listUl li")
-            .expect("invalid chapter selector")
-    });
-
-/// Select chapter link in the chapter entry.
-#[allow(clippy::expect_used)]
-pub(super) static CHAPTER_URL_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile("a").expect("invalid chapter URL selector")
-    });
-
-/// Select image URLs from the chapter page.
-#[allow(clippy::expect_used)]
-pub(super) static PAGE_URL_SELECTOR: Lazy<kuchiki::Selectors> =
-    Lazy::new(|| {
-        kuchiki::Selectors::compile("#_imageList img")
-            .expect("invalid page URL selector")
-    });
+use crate::{
+    sites::SelectorOverrides,
+    SelectorCheck,
+};
+
+/// Name of the web spider, to look up the overrides.
+const SITE: &str = "webtoons";
+
+/// Name and default value of each selector.
+pub(in crate::sites) const DEFAULTS: [(&str, &str); 9] = [
+    ("series_title", "meta[property=\"og:title\"]"),
+    ("series_url", "meta[property=\"og:url\"]"),
+    ("chapter", "#_listUl li"),
+    ("chapter_url", "a"),
+    ("page_url", "#_imageList img"),
+    ("latest", ".daily_section.on a.daily_card_item"),
+    ("popular", ".lst_type1 a.ranking_item"),
+    ("search_result", ".card_lst a.card_item"),
+    ("search_title", ".subj"),
+];
+
+/// CSS selectors to scrape `https://www.webtoons.com`.
+pub(super) struct Selectors {
+    /// Select `<meta property="og:title" content="TITLE" />`
+    pub(super) series_title: kuchiki::Selectors,
+    /// Select `<meta property="og:url" content="URL" />`
+    pub(super) series_url: kuchiki::Selectors,
+    /// Select chapter entries in the chapter list.
+    pub(super) chapter: kuchiki::Selectors,
+    /// Select chapter link in the chapter entry.
+    pub(super) chapter_url: kuchiki::Selectors,
+    /// Select image URLs from the chapter page.
+    pub(super) page_url: kuchiki::Selectors,
+    /// Select series updated today in the daily schedule.
+    pub(super) latest: kuchiki::Selectors,
+    /// Select series entries in the ranking.
+    pub(super) popular: kuchiki::Selectors,
+    /// Select series entries in the search results.
+    pub(super) search_result: kuchiki::Selectors,
+    /// Select series title in the search result entry.
+    pub(super) search_title: kuchiki::Selectors,
+}
+
+impl Selectors {
+    /// Compile the selectors, applying the given overrides.
+    pub(super) fn new(overrides: &SelectorOverrides) -> Self {
+        let compile = |name| overrides.compile(SITE, name, &DEFAULTS);
+
+        Self {
+            series_title: compile("series_title"),
+            series_url: compile("series_url"),
+            chapter: compile("chapter"),
+            chapter_url: compile("chapter_url"),
+            page_url: compile("page_url"),
+            latest: compile("latest"),
+            popular: compile("popular"),
+            search_result: compile("search_result"),
+            search_title: compile("search_title"),
+        }
+    }
+
+    /// Run every selector against `html`.
+    pub(super) fn check(&self, html: &kuchiki::NodeRef) -> Vec<SelectorCheck> {
+        vec![
+            SelectorCheck::run(
+                "series_title",
+                &self.series_title,
+                html,
+                Some("content"),
+            ),
+            SelectorCheck::run(
+                "series_url",
+                &self.series_url,
+                html,
+                Some("content"),
+            ),
+            SelectorCheck::run(
+                "chapter",
+                &self.chapter,
+                html,
+                Some("data-episode-no"),
+            ),
+            SelectorCheck::run_nested(
+                "chapter_url",
+                &self.chapter,
+                &self.chapter_url,
+                html,
+                Some("href"),
+            ),
+            SelectorCheck::run(
+                "page_url",
+                &self.page_url,
+                html,
+                Some("data-url"),
+            ),
+            SelectorCheck::run("latest", &self.latest, html, Some("href")),
+            SelectorCheck::run("popular", &self.popular, html, Some("href")),
+            SelectorCheck::run(
+                "search_result",
+                &self.search_result,
+                html,
+                Some("href"),
+            ),
+            SelectorCheck::run_nested(
+                "search_title",
+                &self.search_result,
+                &self.search_title,
+                html,
+                None,
+            ),
+        ]
+    }
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self::new(&SelectorOverrides::default())
+    }
+}
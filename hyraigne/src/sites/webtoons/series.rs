@@ -1,10 +1,6 @@
 use super::{
     chapter,
-    selectors::{
-        CHAPTER_SELECTOR,
-        SERIES_TITLE_SELECTOR,
-        SERIES_URL_SELECTOR,
-    },
+    selectors::Selectors,
 };
 use crate::{
     types::Pagination,
@@ -16,18 +12,25 @@ use kuchiki::traits::*;
 use url::Url;
 
 /// Scrape series info from the given HTML.
-pub(super) fn scrape_from_html(html: &kuchiki::NodeRef) -> Result<Series> {
+pub(super) fn scrape_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Series> {
     Ok(Series {
-        title: title_from_html(&html)?,
-        url: url_from_html(&html)?,
-        pagination: pagination_from_html(&html)?,
+        title: title_from_html(&html, selectors)?,
+        url: url_from_html(&html, selectors)?,
+        pagination: pagination_from_html(&html, selectors)?,
     })
 }
 
 /// Extract series title from `<meta property="og:title" content="TITLE" />`
 #[allow(clippy::filter_next)]
-fn title_from_html(html: &kuchiki::NodeRef) -> Result<String> {
-    Ok(SERIES_TITLE_SELECTOR
+fn title_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<String> {
+    Ok(selectors
+        .series_title
         .filter(html.descendants().elements())
         .next()
         .ok_or_else(|| Error::Scraping("series title not found".to_owned()))?
@@ -40,8 +43,12 @@ fn title_from_html(html: &kuchiki::NodeRef) -> Result<String> {
 
 /// Extract series URL from `<meta property="og:url" content="URL" />`
 #[allow(clippy::filter_next)]
-fn url_from_html(html: &kuchiki::NodeRef) -> Result<Url> {
-    let element = SERIES_URL_SELECTOR
+fn url_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Url> {
+    let element = selectors
+        .series_url
         .filter(html.descendants().elements())
         .next()
         .ok_or_else(|| Error::Scraping("series URL not found".to_owned()))?;
@@ -58,8 +65,12 @@ fn url_from_html(html: &kuchiki::NodeRef) -> Result<Url> {
 
 /// Infer pagination scheme from the first page of the chapter list.
 #[allow(clippy::filter_map)]
-fn pagination_from_html(html: &kuchiki::NodeRef) -> Result<Pagination> {
-    let chapters = CHAPTER_SELECTOR
+fn pagination_from_html(
+    html: &kuchiki::NodeRef,
+    selectors: &Selectors,
+) -> Result<Pagination> {
+    let chapters = selectors
+        .chapter
         .filter(html.descendants().elements())
         .map(|chapter| chapter::id_from_html(chapter.as_node()))
         .collect::<Result<Vec<_>>>()
@@ -90,7 +101,8 @@ mod tests {
         let html = std::fs::read_to_string(&path).expect("test data");
         let document = kuchiki::parse_html().one(html);
 
-        let series = scrape_from_html(&document).unwrap();
+        let series =
+            scrape_from_html(&document, &Selectors::default()).unwrap();
 
         assert_eq!(series.title, "Hell is Other People");
         assert_eq!(series.url.as_str(), "https://www.webtoons.com/fr/thriller/hell-is-other-people/list?title_no=1841");
@@ -1,59 +1,146 @@
 use super::{
     chapter,
+    listing,
     page,
+    search,
+    selectors::Selectors,
     series,
 };
 use crate::{
-    spiders::HttpClient,
+    sites::{
+        hosts,
+        scraper::{
+            self,
+            ChapterLink,
+            Document,
+            Scraper,
+        },
+    },
     Chapter,
-    Error,
     Filter,
+    LinkKind,
+    Listing,
     Options,
     Page,
     Result,
+    SelectorCheck,
     Series,
+    SeriesSummary,
 };
 use cookie_store::CookieStore;
-use std::path::PathBuf;
+use kuchiki::traits::*;
 use url::Url;
 
 /// A web spider for `https://www.webtoons.com`.
 pub(crate) struct Site {
-    spider: HttpClient,
-    output: PathBuf,
+    selectors: Selectors,
 }
 
 impl Site {
     /// Initialize the web spider with the given options.
+    pub(crate) fn new(options: &Options) -> Self {
+        Self {
+            selectors: Selectors::new(&options.selectors),
+        }
+    }
+}
+
+impl Scraper for Site {
     #[allow(clippy::expect_used)] // Hardcoded values should be valid…
-    pub(crate) fn new(options: Options) -> Self {
+    fn cookies(&self, options: &Options) -> Option<CookieStore> {
         let mut store = CookieStore::default();
         store
             .insert_raw(
                 &ureq::Cookie::new("pagGDPR", "true"),
-                &Url::parse("https://www.webtoons.com/").expect("valid URL"),
+                &options.base_urls.rewrite(
+                    &Url::parse("https://www.webtoons.com/")
+                        .expect("valid URL"),
+                ),
             )
             .expect("pagGDPR cookie");
 
-        Self {
-            spider: HttpClient::new(options.delay, options.retry, Some(store)),
-            output: options.output,
+        Some(store)
+    }
+
+    fn search_url(&self, query: &str) -> Result<Url> {
+        Ok(search_url(query))
+    }
+
+    fn scrape_search(&self, document: &Document) -> Result<Vec<SeriesSummary>> {
+        search::scrape_from_html(document.html()?, &self.selectors)
+    }
+
+    #[allow(clippy::expect_used)] // Hardcoded values should be valid…
+    fn listing_url(&self, listing: Listing, page: u16) -> Result<Option<Url>> {
+        // Listings fit on a single page.
+        if page > 1 {
+            return Ok(None);
         }
+
+        let url = Url::parse(match listing {
+            Listing::Latest => "https://www.webtoons.com/en/dailySchedule",
+            Listing::Popular => "https://www.webtoons.com/en/top",
+        })
+        .expect("valid URL");
+
+        Ok(Some(url))
     }
-}
 
-impl crate::Site for Site {
-    fn get_series(&self, url: &Url) -> Result<Series> {
-        log::info!("scraping series info from {}…", url.as_str());
+    fn scrape_listing(
+        &self,
+        listing: Listing,
+        document: &Document,
+    ) -> Result<Vec<SeriesSummary>> {
+        listing::scrape_from_html(document.html()?, listing, &self.selectors)
+    }
 
-        let html = self.spider.get_html(url)?;
-        let series = series::scrape_from_html(&html).map_err(|err| {
-            Error::Scraping(format!(
-                "failed to scrape serie from {}: {}",
-                url.as_str(),
-                err
-            ))
-        })?;
+    fn check_selectors(&self, html: &str) -> Result<Vec<SelectorCheck>> {
+        let html = kuchiki::parse_html().one(html);
+
+        Ok(self.selectors.check(&html))
+    }
+
+    fn canonical_url(&self, url: &Url) -> Url {
+        hosts::canonicalize(url, "www.webtoons.com", &[])
+    }
+
+    fn classify(&self, url: &Url) -> LinkKind {
+        if url.path().ends_with("/viewer") {
+            LinkKind::Chapter
+        } else {
+            LinkKind::Series
+        }
+    }
+
+    fn parse_chapter_link(&self, url: &Url) -> Option<ChapterLink> {
+        let query = |name| {
+            url.query_pairs()
+                .find(|&(ref key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let title = query("title_no")?;
+        let episode = query("episode_no")?.parse::<u16>().ok()?;
+
+        // The series lives next to its episodes:
+        // `/en/fantasy/title/ep-12/viewer` → `/en/fantasy/title/list`.
+        let mut series = url.clone();
+        series.path_segments_mut().ok()?.pop().pop().push("list");
+        series
+            .query_pairs_mut()
+            .clear()
+            .append_pair("title_no", &title);
+
+        Some(ChapterLink {
+            series,
+            id: f64::from(episode),
+            volume: None,
+            url: url.clone(),
+        })
+    }
+
+    fn scrape_series(&self, _url: &Url, document: &Document) -> Result<Series> {
+        let series =
+            series::scrape_from_html(document.html()?, &self.selectors)?;
 
         log::debug!(
             "scraped info for series `{}`: {} chapters, {} per page",
@@ -65,108 +152,233 @@ impl crate::Site for Site {
         Ok(series)
     }
 
-    fn get_chapters<'a>(
+    fn chapters_url(
         &self,
-        series: &'a Series,
-        filter: Filter,
-    ) -> Result<Vec<Chapter<'a>>> {
-        log::info!("scraping chapter links for series {}…", series.title);
-
+        series: &Series,
+        filter: &Filter,
+        index: u16,
+    ) -> Option<Url> {
         // Fix the chapter range and compute the corresponding page range.
         let first_page = series.pagination.get_page(*filter.range.end());
         let last_page = series.pagination.get_page(*filter.range.start());
 
-        // Fetch every chapter link in the computed page range.
-        let mut chapters = Vec::new();
-        for page in first_page..=last_page {
-            let mut url = series.url.clone();
-            url.query_pairs_mut().append_pair("page", &page.to_string());
-
-            log::info!("extracting chapter from page {}…", page);
-
-            let html = self.spider.get_html(&url)?;
-            chapters.extend(
-                chapter::scrape_from_html(&html, &series).map_err(|err| {
-                    Error::Scraping(format!(
-                        "failed to scrape chapters from {}: {}",
-                        url.as_str(),
-                        err
-                    ))
-                })?,
-            );
+        let page = first_page.checked_add(index)?;
+        if page > last_page {
+            return None;
         }
-        log::debug!("found {} chapters", chapters.len());
 
-        // Trim the chapters list to keep only the requested chapters.
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        let mut chapters = chapters
-            .into_iter()
-            // Chapter IDs are always positive and under u16::MAX.
-            .filter(|chapter| filter.range.contains(&(chapter.id as u16)))
-            .collect::<Vec<_>>();
+        let mut url = series.url.clone();
+        url.query_pairs_mut().append_pair("page", &page.to_string());
+
+        Some(url)
+    }
+
+    fn scrape_chapters<'a>(
+        &self,
+        _url: &Url,
+        series: &'a Series,
+        _filter: &Filter,
+        document: &Document,
+    ) -> Result<Vec<Chapter<'a>>> {
+        chapter::scrape_from_html(document.html()?, series, &self.selectors)
+    }
+
+    fn keep_chapter(&self, chapter: &Chapter<'_>, filter: &Filter) -> bool {
+        scraper::keep_truncated_id(chapter, filter)
+    }
+
+    fn scrape_pages<'a>(
+        &self,
+        chapter: &'a Chapter<'_>,
+        document: &Document,
+    ) -> Result<Vec<Page<'a>>> {
+        page::scrape_from_html(document.html()?, chapter, &self.selectors)
+    }
+}
+
+/// Build the URL of the search results for `query`.
+#[allow(clippy::expect_used)] // Hardcoded values should be valid…
+fn search_url(query: &str) -> Url {
+    let mut url =
+        Url::parse("https://www.webtoons.com/en/search").expect("valid URL");
+    url.query_pairs_mut().append_pair("keyword", query);
+
+    url
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sites::engine::Engine,
+        testing,
+        MemoryTransport,
+        Response,
+        Site as _,
+    };
+    use std::sync::Arc;
 
-        #[allow(clippy::expect_used)] // Shouldn't have NaN & friends here…
-        chapters
-            .sort_by(|a, b| a.partial_cmp(b).expect("abnormal float as ID"));
-        log::debug!("selected {} chapters", chapters.len());
+    const SERIES_URL: &str = "https://www.webtoons.com/fr/thriller/hell-is-other-people/list?title_no=1841";
 
-        Ok(chapters)
+    /// Build the blocking web spider.
+    fn spider(options: Options) -> Engine<Site> {
+        Engine::new(Site::new(&options), options)
     }
 
-    fn get_pages<'a>(&self, chapter: &'a Chapter<'_>) -> Result<Vec<Page<'a>>> {
-        log::info!("scraping page links for chapter {}…", chapter.id);
+    #[test]
+    fn test_get_series_with_retry() {
+        let transport = Arc::new(MemoryTransport::new());
+        let url = Url::parse(SERIES_URL).unwrap();
+        transport.insert(&url, Response::new(503, "Service Unavailable"));
+        testing::serve(&transport, SERIES_URL, "webtoons.com/series.html");
+        let site = spider(testing::options(&transport, "wt-retry"));
 
-        let html = self.spider.get_html(&chapter.url)?;
-        let pages = page::scrape_from_html(&html, chapter).map_err(|err| {
-            Error::Scraping(format!(
-                "failed to pages from {}: {}",
-                chapter.url.as_str(),
-                err
-            ))
-        })?;
+        let series = site.get_series(&url).unwrap();
+
+        assert_eq!(series.title, "Hell is Other People");
+        assert_eq!(transport.requests().len(), 2);
+    }
 
-        log::debug!("found {} pages in chapter {}", pages.len(), chapter.id);
+    #[test]
+    fn test_get_series_failure() {
+        let transport = Arc::new(MemoryTransport::new());
+        let url = Url::parse(SERIES_URL).unwrap();
+        let site = spider(testing::options(&transport, "wt-failure"));
 
-        Ok(pages)
+        assert!(site.get_series(&url).is_err());
     }
 
-    fn mkdir(&self, chapters: &[Chapter<'_>]) -> Result<()> {
-        for chapter in chapters {
-            let path = chapter.path(&self.output);
-            crate::fs::mkdir_p(&path)?;
-        }
+    #[test]
+    fn test_get_series_blocked() {
+        let transport = Arc::new(MemoryTransport::new());
+        let url = Url::parse(SERIES_URL).unwrap();
+        transport.insert(
+            &url,
+            Response::new(
+                503,
+                testing::fixture("challenges/cloudflare_challenge.html"),
+            )
+            .with_header("Server", "cloudflare"),
+        );
+        let site = spider(testing::options(&transport, "wt-blocked"));
 
-        Ok(())
+        let err = site.get_series(&url).err().unwrap();
+
+        assert!(matches!(err, crate::Error::Blocked { .. }));
+        // Challenges are not retried.
+        assert_eq!(transport.requests().len(), 1);
     }
 
-    fn download(&self, pages: &[Page<'_>]) -> Result<()> {
-        log::info!(
-            "downloading {} pages for chapter {}…",
-            pages.len(),
-            pages[0].chapter.id,
+    #[test]
+    fn test_get_series_from_base_url() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(
+            &transport,
+            "http://127.0.0.1:8080/fr/thriller/hell-is-other-people/list?title_no=1841",
+            "webtoons.com/series.html",
         );
+        let options = testing::options(&transport, "wt-base-url")
+            .with_base_url(
+                &Url::parse("https://www.webtoons.com/").unwrap(),
+                &Url::parse("http://127.0.0.1:8080/").unwrap(),
+            );
+        let site = spider(options);
+        let url = Url::parse(SERIES_URL).unwrap();
 
-        let mut bytes: Vec<u8> = Vec::new();
+        let series = site.get_series(&url).unwrap();
 
-        for page in pages.iter() {
-            // Compute the image path.
-            let path = page.path(&self.output);
+        assert_eq!(series.title, "Hell is Other People");
+        assert_eq!(transport.requests()[0].host_str(), Some("127.0.0.1"));
+    }
 
-            // Skip it if it has already been downloaded.
-            if path.exists() {
-                log::debug!("{} already exists, skip", path.display());
-                continue;
-            }
+    #[test]
+    fn test_get_series_from_mobile_url() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "webtoons.com/series.html");
+        let site = spider(testing::options(&transport, "wt-mobile"));
+        let url = Url::parse(&SERIES_URL.replace("www.", "m.")).unwrap();
 
-            log::info!("downloading {}…", path.display());
-            self.spider
-                .get_image(&page.main, &page.chapter.url, &mut bytes)?;
+        let series = site.get_series(&url).unwrap();
 
-            crate::fs::atomic_save(&path, &bytes)?;
+        assert_eq!(series.title, "Hell is Other People");
+        assert_eq!(transport.requests()[0].as_str(), SERIES_URL);
+    }
 
-            bytes.clear();
-        }
+    #[test]
+    fn test_get_chapters_and_pages() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "webtoons.com/series.html");
+        testing::serve(
+            &transport,
+            &format!("{}&page=1", SERIES_URL),
+            "webtoons.com/series.html",
+        );
+        testing::serve(
+            &transport,
+            "https://www.webtoons.com/fr/thriller/hell-is-other-people/ep-78/viewer?title_no=1841&episode_no=78",
+            "webtoons.com/chapter.html",
+        );
+        let site = spider(testing::options(&transport, "wt-chapters"));
+        let url = Url::parse(SERIES_URL).unwrap();
 
-        Ok(())
+        let series = site.get_series(&url).unwrap();
+        let filter = Filter::new(75..=78, None, Vec::new());
+        let chapters = site.get_chapters(&series, filter).unwrap();
+
+        assert_eq!(
+            chapters
+                .iter()
+                .map(|chapter| chapter.id)
+                .collect::<Vec<_>>(),
+            vec![75.0, 76.0, 77.0, 78.0]
+        );
+
+        let pages = site.get_pages(&chapters[3]).unwrap();
+
+        assert_eq!(pages.len(), 32);
+    }
+
+    #[test]
+    fn test_get_chapter_from_link() {
+        let transport = Arc::new(MemoryTransport::new());
+        testing::serve(&transport, SERIES_URL, "webtoons.com/series.html");
+        let site = spider(testing::options(&transport, "wt-chapter-link"));
+        let url = Url::parse("https://www.webtoons.com/fr/thriller/hell-is-other-people/ep-5/viewer?title_no=1841&episode_no=5").unwrap();
+
+        assert_eq!(site.classify(&url), LinkKind::Chapter);
+        let series = site.get_series(&url).unwrap();
+        let chapter = site.get_chapter(&series, &url).unwrap();
+
+        assert_eq!(series.title, "Hell is Other People");
+        assert_eq!(chapter.id, 5.0);
+        assert_eq!(chapter.url, url);
+        // Everything but the series is known from the URL.
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_get_series_with_retry() {
+        use crate::{
+            sites::async_engine::AsyncEngine,
+            AsyncSite as _,
+        };
+
+        let transport = Arc::new(MemoryTransport::new());
+        let url = Url::parse(SERIES_URL).unwrap();
+        transport.insert(&url, Response::new(503, "Service Unavailable"));
+        testing::serve(&transport, SERIES_URL, "webtoons.com/series.html");
+        // The blocking transport runs on the threads for blocking tasks.
+        let options = testing::options(&transport, "wt-async-retry");
+        let site = AsyncEngine::new(Site::new(&options), options);
+
+        let series = testing::block_on(site.get_series(&url)).unwrap();
+
+        assert_eq!(series.title, "Hell is Other People");
+        assert_eq!(transport.requests().len(), 2);
     }
 }
+
+// }}}
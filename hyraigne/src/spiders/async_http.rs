@@ -0,0 +1,122 @@
+use super::{
+    http::{
+        self,
+        Policy,
+    },
+    transport::{
+        AsyncAgentTransport,
+        AsyncTransport,
+        BlockingTransport,
+        Request,
+        Response,
+    },
+};
+use crate::{
+    Options,
+    Result,
+};
+use cookie_store::CookieStore;
+use std::sync::Arc;
+use url::Url;
+
+/// The async counterpart of the lightweight spider.
+///
+/// Requests are sent and retried exactly like the blocking spider does, but
+/// without blocking the thread (while waiting for the website, or between the
+/// requests).
+pub(crate) struct AsyncSpider {
+    /// HTTP transport.
+    transport: Arc<dyn AsyncTransport>,
+    /// How the requests are sent and retried.
+    policy: Policy,
+}
+
+impl AsyncSpider {
+    /// Initialize a new web spider.
+    ///
+    /// The cookies are only used by the default transports.
+    pub(crate) fn new(
+        options: &Options,
+        cookie_store: Option<CookieStore>,
+    ) -> Self {
+        let blocking = options.transport.is_some()
+            || options.cassette.is_some()
+            || options.cache.is_some();
+        let transport = match options.async_transport {
+            Some(ref transport) => Arc::clone(transport),
+            // Only available as blocking transports.
+            None if blocking => {
+                Arc::new(BlockingTransport::new(http::transport(
+                    options,
+                    cookie_store,
+                )))
+            },
+            None => {
+                Arc::new(AsyncAgentTransport::new(
+                    cookie_store,
+                    options.proxy.as_ref(),
+                    options.cookie_jar.clone(),
+                ))
+            },
+        };
+
+        Self {
+            transport,
+            policy: Policy::new(options),
+        }
+    }
+
+    /// Retrieve the document at `url`, of the `accept`ed type.
+    pub(crate) async fn get(&self, url: &Url, accept: &str) -> Result<Vec<u8>> {
+        self.send(Request::get(url), accept).await
+    }
+
+    /// Send `request`, to retrieve a document of the `accept`ed type.
+    pub(crate) async fn send(
+        &self,
+        request: Request,
+        accept: &str,
+    ) -> Result<Vec<u8>> {
+        let request = request.with_header("accept", accept);
+
+        Ok(self.call(&request).await?.body)
+    }
+
+    /// Download the specified page in the given buffer.
+    pub(crate) async fn get_image(
+        &self,
+        url: &Url,
+        referer: &Url,
+        buf: &mut Vec<u8>,
+    ) -> Result<()> {
+        let request = self.policy.image_request(url, referer);
+
+        let response = self.call(&request).await?;
+
+        buf.extend_from_slice(&response.body);
+
+        Ok(())
+    }
+
+    /// Execute a request and handle retries.
+    async fn call(&self, request: &Request) -> Result<Response> {
+        let request = self.policy.prepare(request);
+        let cancellation = &self.policy.cancellation;
+
+        // Wait a bit, don't overload the site.
+        cancellation.sleep_async(self.policy.delay).await?;
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            cancellation.check()?;
+            let response = self.transport.send(&request).await?;
+
+            match self.policy.check(request.url(), &response, attempt)? {
+                Some(delay) => cancellation.sleep_async(delay).await?,
+                None => return Ok(response),
+            }
+        }
+    }
+}
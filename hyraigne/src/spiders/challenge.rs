@@ -0,0 +1,132 @@
+//! Detection of the anti-bot challenges (Cloudflare, …) served instead of the
+//! requested content.
+
+use super::transport::Response;
+
+/// Number of bytes of the body searched for page markers.
+///
+/// Challenge pages are small, and their markers are never far from the top.
+const MAX_SCANNED_BYTES: usize = 64 * 1024;
+
+/// Markers only found on challenge pages, with the protection they belong to.
+const PAGE_MARKERS: [(&str, &str); 5] = [
+    ("window._cf_chl_opt", "a Cloudflare challenge"),
+    ("cf-browser-verification", "a Cloudflare challenge"),
+    ("<title>Just a moment...</title>", "a Cloudflare challenge"),
+    (
+        "<title>Attention Required! | Cloudflare</title>",
+        "the Cloudflare firewall",
+    ),
+    ("/.well-known/ddos-guard/", "a DDoS-Guard challenge"),
+];
+
+/// Markers found on challenge pages, but also on regular pages.
+///
+/// Only considered for error responses from an anti-bot service.
+const WEAK_MARKERS: [&str; 2] = ["/cdn-cgi/challenge-platform/", "captcha"];
+
+/// Servers of the anti-bot services, with the protection they provide.
+const SERVERS: [(&str, &str); 2] = [
+    ("cloudflare", "a Cloudflare challenge"),
+    ("ddos-guard", "a DDoS-Guard challenge"),
+];
+
+/// Return the protection that blocked the request, if `response` is a
+/// challenge.
+pub(crate) fn detect(response: &Response) -> Option<&'static str> {
+    // Cloudflare is kind enough to flag its challenges.
+    if response.header("cf-mitigated").is_some() {
+        return Some("a Cloudflare challenge");
+    }
+
+    let body = &response.body[..response.body.len().min(MAX_SCANNED_BYTES)];
+    let body = String::from_utf8_lossy(body);
+
+    if let Some(&(_, protection)) = PAGE_MARKERS
+        .iter()
+        .find(|&&(marker, _)| body.contains(marker))
+    {
+        return Some(protection);
+    }
+
+    if !matches!(response.status, 403 | 429 | 503) {
+        return None;
+    }
+    let server = response.header("server")?.to_ascii_lowercase();
+    let &(_, protection) =
+        SERVERS.iter().find(|&&(name, _)| server.contains(name))?;
+
+    if WEAK_MARKERS.iter().any(|&marker| body.contains(marker)) {
+        return Some(protection);
+    }
+    None
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_cloudflare_challenge() {
+        let response = Response::new(
+            403,
+            testing::fixture("challenges/cloudflare_challenge.html"),
+        )
+        .with_header("Server", "cloudflare");
+
+        assert_eq!(detect(&response), Some("a Cloudflare challenge"));
+    }
+
+    #[test]
+    fn test_cloudflare_mitigated_header() {
+        let response =
+            Response::new(403, "").with_header("cf-mitigated", "challenge");
+
+        assert_eq!(detect(&response), Some("a Cloudflare challenge"));
+    }
+
+    #[test]
+    fn test_cloudflare_firewall() {
+        let response = Response::new(
+            403,
+            testing::fixture("challenges/cloudflare_blocked.html"),
+        );
+
+        assert_eq!(detect(&response), Some("the Cloudflare firewall"));
+    }
+
+    #[test]
+    fn test_ddos_guard() {
+        let response =
+            Response::new(200, testing::fixture("challenges/ddos_guard.html"));
+
+        assert_eq!(detect(&response), Some("a DDoS-Guard challenge"));
+    }
+
+    #[test]
+    fn test_regular_pages() {
+        for path in &[
+            "mangakakalot.com/series.html",
+            "webtoonscan.com/series.html",
+            "webtoons.com/series.html",
+        ] {
+            let response = Response::new(200, testing::fixture(path))
+                .with_header("Server", "cloudflare");
+
+            assert_eq!(detect(&response), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_cloudflare_error() {
+        let response = Response::new(503, "<html>Service Unavailable</html>")
+            .with_header("Server", "cloudflare");
+
+        assert_eq!(detect(&response), None);
+    }
+}
+
+// }}}
@@ -1,13 +1,24 @@
+use super::{
+    challenge,
+    transport::{
+        AgentTransport,
+        CachingTransport,
+        RecordingTransport,
+        Request,
+        Response,
+        Transport,
+    },
+};
 use crate::{
+    types::BaseUrls,
+    CancellationToken,
     Error,
+    Options,
     Result,
 };
 use cookie_store::CookieStore;
-use kuchiki::traits::*;
-use serde::de::DeserializeOwned;
 use std::{
-    io::Read,
-    thread,
+    sync::Arc,
     time,
 };
 use url::Url;
@@ -18,49 +29,40 @@ use url::Url;
 /// information can be extracted from the HTML served statically (without
 /// executing JS code dynamically).
 pub(crate) struct Spider {
-    /// HTTP client.
-    agent: ureq::Agent,
-    /// Delay between each request.
-    delay: time::Duration,
-    /// Max number of retry for each request.
-    retry: u8,
+    /// HTTP transport.
+    transport: Arc<dyn Transport>,
+    /// How the requests are sent and retried.
+    policy: Policy,
 }
 
 impl Spider {
     /// Initialize a new web spider.
+    ///
+    /// The cookies are only used by the default transport.
     pub(crate) fn new(
-        delay: time::Duration,
-        retry: u8,
+        options: &Options,
         cookie_store: Option<CookieStore>,
     ) -> Self {
-        let agent = cookie_store.map_or_else(ureq::Agent::new, |store| {
-            ureq::builder().cookie_store(store).build()
-        });
-
         Self {
-            agent,
-            delay,
-            retry,
+            transport: transport(options, cookie_store),
+            policy: Policy::new(options),
         }
     }
 
-    /// Retrieve and parse the page at `url`.
-    pub(crate) fn get_html(&self, url: &Url) -> Result<kuchiki::NodeRef> {
-        let request = self
-            .agent
-            .request_url("GET", url)
-            .set("accept", "text/html");
-
-        let response = self.call(&request, url)?;
+    /// Retrieve the document at `url`, of the `accept`ed type.
+    pub(crate) fn get(&self, url: &Url, accept: &str) -> Result<Vec<u8>> {
+        self.send(Request::get(url), accept)
+    }
 
-        let html = response.into_string().map_err(|err| {
-            log::error!("failed to read HTML from {}: {}", url.as_str(), err);
-            Error::Network {
-                url: url.to_string(),
-            }
-        })?;
+    /// Send `request`, to retrieve a document of the `accept`ed type.
+    pub(crate) fn send(
+        &self,
+        request: Request,
+        accept: &str,
+    ) -> Result<Vec<u8>> {
+        let request = request.with_header("accept", accept);
 
-        Ok(kuchiki::parse_html().one(html))
+        Ok(self.call(&request)?.body)
     }
 
     /// Download the specified page in the given buffer.
@@ -73,93 +75,188 @@ impl Spider {
         referer: &Url,
         buf: &mut Vec<u8>,
     ) -> Result<()> {
-        let request = self
-            .agent
-            .request_url("GET", url)
-            .set("accept", "image/*")
-            .set("Referer", referer.as_str());
+        let request = self.policy.image_request(url, referer);
 
-        let response = self.call(&request, url)?;
+        let response = self.call(&request)?;
 
-        response.into_reader().read_to_end(buf).map_err(|err| {
-            log::error!("failed to read image from {}: {}", url.as_str(), err);
-            Error::Network {
-                url: url.to_string(),
-            }
-        })?;
+        buf.extend_from_slice(&response.body);
 
         Ok(())
     }
 
-    /// Make a call at `url` and parse the response.
-    pub(crate) fn get_json<T>(&self, url: &Url) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        let request = self
-            .agent
-            .request_url("GET", url)
-            .set("accept", "application/json");
-        let response = self.call(&request, url)?;
-
-        serde_json::from_reader(response.into_reader()).map_err(|err| {
-            log::error!("failed to read JSON from {}: {}", url.as_str(), err);
-            Error::Payload {
-                url: url.to_string(),
-            }
-        })
-    }
-
     /// Execute a request and handle retries.
-    fn call(
-        &self,
-        request: &ureq::Request,
-        url: &Url,
-    ) -> Result<ureq::Response> {
+    fn call(&self, request: &Request) -> Result<Response> {
+        let request = self.policy.prepare(request);
+
         // Wait a bit, don't overload the site.
-        thread::sleep(self.delay);
+        self.policy.cancellation.sleep(self.policy.delay)?;
 
-        let mut i = 0;
+        let mut attempt = 0;
         loop {
-            i += 1;
+            attempt += 1;
+
+            self.policy.cancellation.check()?;
+            let response = self.transport.send(&request)?;
 
-            let res = request.clone().call();
+            match self.policy.check(request.url(), &response, attempt)? {
+                Some(delay) => self.policy.cancellation.sleep(delay)?,
+                None => return Ok(response),
+            }
+        }
+    }
+}
 
-            if let Err(ureq::Error::Status(code, ref response)) = res {
-                // If we got a retryable error, we try again.
-                if is_request_retryable(code) && i <= self.retry {
-                    let delay = self.retry_delay(&response);
+/// Build the transport described by `options`.
+///
+/// The cookies are only used by the default transport.
+pub(super) fn transport(
+    options: &Options,
+    cookie_store: Option<CookieStore>,
+) -> Arc<dyn Transport> {
+    let mut transport = options.transport.as_ref().map_or_else(
+        || {
+            Arc::new(AgentTransport::new(
+                cookie_store,
+                options.proxy.as_ref(),
+                options.cookie_jar.clone(),
+            )) as Arc<dyn Transport>
+        },
+        Arc::clone,
+    );
+    if let Some(ref cassette) = options.cassette {
+        transport =
+            Arc::new(RecordingTransport::new(transport, Arc::clone(cassette)));
+    }
+    // Cache on top, so that only the network traffic is recorded.
+    if let Some(ref cache) = options.cache {
+        transport =
+            Arc::new(CachingTransport::new(transport, Arc::clone(cache)));
+    }
 
-                    log::debug!(
-                        "GET {} failed with status {}: retry in {} ms…",
-                        url.as_str(),
-                        code,
-                        delay.as_millis()
-                    );
+    transport
+}
 
-                    thread::sleep(delay);
-                    continue;
-                }
+/// How the requests are sent and retried, whatever the HTTP client.
+pub(super) struct Policy {
+    /// Delay between each request.
+    pub(super) delay: time::Duration,
+    /// Max number of retry for each request.
+    retry: u8,
+    /// Base URLs remapped to other ones.
+    base_urls: BaseUrls,
+    /// Extra headers sent with every request.
+    headers: Vec<(String, String)>,
+    /// Token to stop the requests.
+    pub(super) cancellation: CancellationToken,
+}
+
+impl Policy {
+    /// Initialize the policy from the given options.
+    pub(super) fn new(options: &Options) -> Self {
+        Self {
+            delay: options.delay,
+            retry: options.retry,
+            base_urls: options.base_urls.clone(),
+            headers: options.headers.clone(),
+            cancellation: options.cancellation.clone(),
+        }
+    }
+
+    /// Build the request to download the image at `url`.
+    pub(super) fn image_request(&self, url: &Url, referer: &Url) -> Request {
+        Request::get(url)
+            .with_header("accept", "image/*")
+            .with_header("Referer", self.base_urls.rewrite(referer).as_str())
+    }
+
+    /// Rewrite the URL of `request` and add the extra headers.
+    pub(super) fn prepare(&self, request: &Request) -> Request {
+        let mut request = Request {
+            url: self.base_urls.rewrite(&request.url),
+            headers: request.headers.clone(),
+            body: request.body.clone(),
+        };
+        // Headers set by the web spider take precedence.
+        for &(ref name, ref value) in &self.headers {
+            if request.header(name).is_none() {
+                request = request.with_header(name, value);
             }
+        }
+
+        request
+    }
 
-            return res.map_err(|err| {
-                log::error!("HTTP request failed: {}", err);
-                Error::Network {
-                    url: url.to_string(),
-                }
+    /// Check the `attempt`-th response to a request for `url`.
+    ///
+    /// Return the delay to wait before retrying, if the request should be
+    /// retried.
+    pub(super) fn check(
+        &self,
+        url: &Url,
+        response: &Response,
+        attempt: u8,
+    ) -> Result<Option<time::Duration>> {
+        let code = response.status();
+
+        // No point in retrying, the challenge won't go away by itself.
+        if let Some(protection) = challenge::detect(response) {
+            log::error!(
+                "HTTP request blocked: {}: {} (status code {})",
+                url.as_str(),
+                protection,
+                code
+            );
+            return Err(Error::Blocked {
+                url: url.to_string(),
+                protection,
             });
         }
+
+        // If we got a retryable error, we try again.
+        if is_request_retryable(code) && attempt <= self.retry {
+            let delay = self.retry_delay(response);
+
+            log::debug!(
+                "GET {} failed with status {}: retry in {} ms…",
+                url.as_str(),
+                code,
+                delay.as_millis()
+            );
+
+            return Ok(Some(delay));
+        }
+
+        if code >= 400 {
+            log::error!(
+                "HTTP request failed: {}: status code {}",
+                url.as_str(),
+                code
+            );
+            return Err(Error::from_status(
+                url.as_str(),
+                code,
+                retry_after(response),
+                &response.body,
+            ));
+        }
+
+        Ok(None)
     }
 
     /// Compute the delay to wait before retrying a failed request.
-    fn retry_delay(&self, response: &ureq::Response) -> time::Duration {
-        response
-            .header("retry-after")
-            .and_then(|h| h.parse::<u64>().ok())
-            .map_or(self.delay, time::Duration::from_secs)
+    fn retry_delay(&self, response: &Response) -> time::Duration {
+        retry_after(response).unwrap_or(self.delay)
     }
 }
 
+/// Return the delay requested by the website before retrying, if any.
+fn retry_after(response: &Response) -> Option<time::Duration> {
+    response
+        .header("retry-after")
+        .and_then(|h| h.parse::<u64>().ok())
+        .map(time::Duration::from_secs)
+}
+
 /// Test if request failed with a retryable error.
 fn is_request_retryable(http_status: u16) -> bool {
     // 429 is Too Many Requests
@@ -1,6 +1,23 @@
 //! Provides various web spiders to scrape a wide range of websites, from simple
 //! to complex ones (relying on JS fuckery).
 
+#[cfg(feature = "async")]
+mod async_http;
+mod challenge;
 mod http;
+mod transport;
 
+#[cfg(feature = "async")]
+pub(crate) use async_http::AsyncSpider as AsyncHttpClient;
 pub(crate) use http::Spider as HttpClient;
+#[cfg(feature = "async")]
+pub use transport::AsyncTransport;
+pub use transport::{
+    Cassette,
+    CookieJar,
+    HttpCache,
+    MemoryTransport,
+    Request,
+    Response,
+    Transport,
+};
@@ -0,0 +1,113 @@
+use super::CookieJar;
+use super::{
+    Request,
+    Response,
+    Transport,
+};
+use crate::{
+    Error,
+    Proxy,
+    Result,
+};
+use cookie_store::CookieStore;
+use std::{
+    io::Read,
+    sync::Arc,
+};
+
+/// A transport built on top of `ureq`.
+pub(crate) struct AgentTransport {
+    /// HTTP client.
+    agent: ureq::Agent,
+
+    /// Cookie jar kept in sync with the client cookies, if any.
+    cookie_jar: Option<Arc<CookieJar>>,
+}
+
+impl AgentTransport {
+    /// Initialize a new transport, with the given cookies and proxy.
+    ///
+    /// The cookies of `cookie_jar` take precedence over those of
+    /// `cookie_store`, and the jar receives the cookies set by the websites.
+    pub(crate) fn new(
+        cookie_store: Option<CookieStore>,
+        proxy: Option<&Proxy>,
+        cookie_jar: Option<Arc<CookieJar>>,
+    ) -> Self {
+        let mut store = cookie_store.unwrap_or_default();
+        if let Some(ref jar) = cookie_jar {
+            jar.fill(&mut store);
+        }
+        let mut builder = ureq::builder().cookie_store(store);
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.to_ureq());
+        }
+
+        Self {
+            agent: builder.build(),
+            cookie_jar,
+        }
+    }
+}
+
+impl Transport for AgentTransport {
+    fn send(&self, request: &Request) -> Result<Response> {
+        let url = request.url();
+        let mut call = self.agent.request_url(request.method(), url);
+        for &(ref name, ref value) in request.headers() {
+            call = call.set(name, value);
+        }
+
+        let response = match request.body() {
+            Some(body) => call.send_bytes(body),
+            None => call.call(),
+        };
+        let response = match response {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => {
+                log::error!("HTTP request failed: {}", err);
+                return Err(Error::Network {
+                    url: url.to_string(),
+                    source: Box::new(err),
+                });
+            },
+        };
+
+        if let Some(ref jar) = self.cookie_jar {
+            if response.header("set-cookie").is_some() {
+                jar.absorb(&self.agent.cookie_store());
+            }
+        }
+
+        let status = response.status();
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = response.header(&name)?.to_owned();
+                Some((name, value))
+            })
+            .collect();
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|err| {
+                log::error!(
+                    "failed to read response from {}: {}",
+                    url.as_str(),
+                    err
+                );
+                Error::Network {
+                    url: url.to_string(),
+                    source: Box::new(err),
+                }
+            })?;
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
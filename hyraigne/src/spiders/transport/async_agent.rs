@@ -0,0 +1,352 @@
+use super::{
+    AsyncTransport,
+    CookieJar,
+    Request,
+    Response,
+};
+use crate::{
+    Error,
+    Proxy,
+    Result,
+};
+use cookie_store::CookieStore;
+use std::sync::{
+    Arc,
+    Mutex,
+    MutexGuard,
+    PoisonError,
+};
+use url::Url;
+
+/// Maximum number of redirections followed for a single request.
+const MAX_REDIRECTIONS: usize = 10;
+
+/// Headers that aren't forwarded when redirected to another host.
+const CREDENTIAL_HEADERS: [&str; 3] =
+    ["authorization", "cookie", "proxy-authorization"];
+
+/// An async transport built on top of `reqwest`.
+pub(crate) struct AsyncAgentTransport {
+    /// HTTP client.
+    client: reqwest::Client,
+
+    /// Cookies sent along the requests.
+    cookie_store: Mutex<CookieStore>,
+
+    /// Cookie jar kept in sync with the client cookies, if any.
+    cookie_jar: Option<Arc<CookieJar>>,
+}
+
+impl AsyncAgentTransport {
+    /// Initialize a new transport, with the given cookies and proxy.
+    ///
+    /// The cookies of `cookie_jar` take precedence over those of
+    /// `cookie_store`, and the jar receives the cookies set by the websites.
+    #[allow(clippy::expect_used)] // Only fails if TLS can't be initialized.
+    pub(crate) fn new(
+        cookie_store: Option<CookieStore>,
+        proxy: Option<&Proxy>,
+        cookie_jar: Option<Arc<CookieJar>>,
+    ) -> Self {
+        let mut store = cookie_store.unwrap_or_default();
+        if let Some(ref jar) = cookie_jar {
+            jar.fill(&mut store);
+        }
+        // Redirections are followed by hand, to handle the cookies at each hop.
+        let mut builder = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none());
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.to_reqwest());
+        }
+
+        Self {
+            client: builder.build().expect("HTTP client"),
+            cookie_store: Mutex::new(store),
+            cookie_jar,
+        }
+    }
+
+    /// Return the value of the `Cookie` header for `url`, if any.
+    fn cookie_header(&self, url: &Url) -> Option<String> {
+        let store = self.lock();
+        let cookies = store
+            .get_request_cookies(url)
+            .map(|cookie| format!("{}={}", cookie.name(), cookie.value()))
+            .collect::<Vec<_>>();
+
+        if cookies.is_empty() {
+            None
+        } else {
+            Some(cookies.join("; "))
+        }
+    }
+
+    /// Store the cookies set by the response to a request for `url`.
+    fn store_cookies(&self, url: &Url, headers: &[(String, String)]) {
+        let mut store = self.lock();
+        let mut updated = false;
+
+        for &(ref name, ref value) in headers {
+            if !name.eq_ignore_ascii_case("set-cookie") {
+                continue;
+            }
+            match store.parse(value, url) {
+                Ok(_) => updated = true,
+                Err(err) => log::debug!("invalid cookie from {}: {}", url, err),
+            }
+        }
+
+        if let (true, Some(jar)) = (updated, self.cookie_jar.as_ref()) {
+            jar.absorb(&store);
+        }
+    }
+
+    /// Send a single request, without following redirections.
+    async fn call(
+        &self,
+        url: &Url,
+        headers: &[(String, String)],
+        body: Option<&[u8]>,
+    ) -> Result<Response> {
+        let mut call = match body {
+            Some(body) => self.client.post(url.as_str()).body(body.to_vec()),
+            None => self.client.get(url.as_str()),
+        };
+        for &(ref name, ref value) in headers {
+            call = call.header(name.as_str(), value.as_str());
+        }
+        if let Some(cookies) = self.cookie_header(url) {
+            call = call.header("cookie", cookies);
+        }
+
+        let response = call.send().await.map_err(|err| {
+            log::error!("HTTP request failed: {}", err);
+            Error::Network {
+                url: url.to_string(),
+                source: Box::new(err),
+            }
+        })?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?.to_owned();
+                Some((name.as_str().to_owned(), value))
+            })
+            .collect::<Vec<_>>();
+        self.store_cookies(url, &headers);
+
+        let body = response.bytes().await.map_err(|err| {
+            log::error!(
+                "failed to read response from {}: {}",
+                url.as_str(),
+                err
+            );
+            Error::Network {
+                url: url.to_string(),
+                source: Box::new(err),
+            }
+        })?;
+
+        Ok(Response {
+            status,
+            headers,
+            body: body.to_vec(),
+        })
+    }
+
+    /// Lock the cookie store.
+    fn lock(&self) -> MutexGuard<'_, CookieStore> {
+        // Worst case, a cookie update was lost.
+        self.cookie_store
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransport for AsyncAgentTransport {
+    async fn send(&self, request: &Request) -> Result<Response> {
+        let mut url = request.url().clone();
+        let mut headers = request.headers().to_vec();
+        let mut body = request.body().map(<[u8]>::to_vec);
+
+        for _ in 0..=MAX_REDIRECTIONS {
+            let response = self.call(&url, &headers, body.as_deref()).await?;
+            let location = match redirection(&url, &response) {
+                Some(location) => location,
+                None => return Ok(response),
+            };
+
+            // Only 307 and 308 resend the form, the others switch to `GET`.
+            if !matches!(response.status, 307 | 308) && body.take().is_some() {
+                headers.retain(|&(ref name, _)| {
+                    !name.eq_ignore_ascii_case("content-type")
+                });
+            }
+            // Credentials are only meant for the host they were sent to.
+            if location.host_str() != url.host_str() {
+                headers.retain(|&(ref name, _)| {
+                    !CREDENTIAL_HEADERS
+                        .iter()
+                        .any(|header| name.eq_ignore_ascii_case(header))
+                });
+            }
+            log::debug!("redirected from {} to {}", url, location);
+            url = location;
+        }
+
+        Err(Error::Network {
+            url: request.url().to_string(),
+            source: "too many redirections".into(),
+        })
+    }
+}
+
+/// Return the target of the redirection answered for `url`, if any.
+fn redirection(url: &Url, response: &Response) -> Option<Url> {
+    if !matches!(response.status, 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+
+    let location = response.header("location")?;
+    url.join(location)
+        .map_err(|err| {
+            log::warn!("invalid redirection from {}: {}", url, err);
+        })
+        .ok()
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use std::{
+        io::{
+            BufRead,
+            BufReader,
+            Write,
+        },
+        net::TcpListener,
+        thread,
+    };
+
+    #[test]
+    fn test_redirection() {
+        let url = Url::parse("https://example.com/series/42").unwrap();
+        let redirect = |status| {
+            let response =
+                Response::new(status, "").with_header("Location", "../login");
+            redirection(&url, &response).map(|url| url.to_string())
+        };
+
+        assert_eq!(redirect(302).as_deref(), Some("https://example.com/login"));
+        assert_eq!(redirect(308).as_deref(), Some("https://example.com/login"));
+        assert_eq!(redirect(200), None);
+    }
+
+    /// Answer the next connection to `listener` with `reply`.
+    ///
+    /// Return the request headers, lowercased.
+    fn answer(listener: &TcpListener, reply: &str) -> Vec<String> {
+        let (mut stream, _) = listener.accept().unwrap();
+        let headers = BufReader::new(&stream)
+            .lines()
+            .map(|line| line.expect("request line").to_lowercase())
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect();
+        stream.write_all(reply.as_bytes()).unwrap();
+
+        headers
+    }
+
+    #[test]
+    fn test_cookies_across_redirections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        // Answer a redirection setting a cookie, then echo the cookies.
+        let server = thread::spawn(move || {
+            let first = answer(
+                &listener,
+                "HTTP/1.1 302 Found\r\nLocation: /target\r\n\
+                 Set-Cookie: session=42; Path=/\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+            let second = answer(
+                &listener,
+                "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\
+                 Connection: close\r\n\r\n",
+            );
+            [first, second]
+        });
+        let transport = AsyncAgentTransport::new(None, None, None);
+        let url = Url::parse(&format!("{}/source", base)).unwrap();
+
+        let response = testing::block_on(async {
+            transport.send(&Request::get(&url)).await.unwrap()
+        });
+        let [first, second] = server.join().unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert!(!first.iter().any(|header| header.starts_with("cookie:")));
+        assert!(second.contains(&"cookie: session=42".to_owned()));
+    }
+
+    #[test]
+    fn test_credentials_across_hosts() {
+        let source = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/source",
+            source.local_addr().unwrap()
+        ))
+        .unwrap();
+        // Same machine, but another hostname.
+        let location = format!(
+            "http://localhost:{}/target",
+            target.local_addr().unwrap().port()
+        );
+        let server = thread::spawn(move || {
+            let first = answer(
+                &source,
+                &format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {}\r\n\
+                     Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    location
+                ),
+            );
+            let second = answer(
+                &target,
+                "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\
+                 Connection: close\r\n\r\n",
+            );
+            [first, second]
+        });
+        let transport = AsyncAgentTransport::new(None, None, None);
+        let request = Request::get(&url)
+            .with_header("Authorization", "Basic Zm9vOmJhcg==")
+            .with_header("Cookie", "session=42")
+            .with_header("Referer", url.as_str());
+
+        let response = testing::block_on(async {
+            transport.send(&request).await.unwrap()
+        });
+        let [first, second] = server.join().unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert!(first.contains(&"authorization: basic zm9vomjhcg==".to_owned()));
+        assert!(first.contains(&"cookie: session=42".to_owned()));
+        assert!(!second.iter().any(|header| {
+            header.starts_with("authorization:")
+                || header.starts_with("cookie:")
+        }));
+        assert!(second.iter().any(|header| header.starts_with("referer:")));
+    }
+}
+
+// }}}
@@ -0,0 +1,46 @@
+use super::{
+    AsyncTransport,
+    Request,
+    Response,
+    Transport,
+};
+use crate::{
+    Error,
+    Result,
+};
+use std::sync::Arc;
+
+/// Run a blocking transport on the threads dedicated to blocking tasks.
+///
+/// This allows the async web spiders to use the cassettes, the HTTP cache and
+/// the custom blocking transports.
+pub(crate) struct BlockingTransport {
+    /// The blocking transport.
+    transport: Arc<dyn Transport>,
+}
+
+impl BlockingTransport {
+    /// Run `transport` on the threads dedicated to blocking tasks.
+    pub(crate) fn new(transport: Arc<dyn Transport>) -> Self {
+        Self { transport }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransport for BlockingTransport {
+    async fn send(&self, request: &Request) -> Result<Response> {
+        let transport = Arc::clone(&self.transport);
+        let request = request.clone();
+        let url = request.url().to_string();
+
+        tokio::task::spawn_blocking(move || transport.send(&request))
+            .await
+            .map_err(|err| {
+                log::error!("HTTP request failed: {}", err);
+                Error::Network {
+                    url,
+                    source: Box::new(err),
+                }
+            })?
+    }
+}
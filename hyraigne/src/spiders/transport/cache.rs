@@ -0,0 +1,445 @@
+use super::{
+    Request,
+    Response,
+    Transport,
+};
+use crate::{
+    spiders::challenge,
+    Error,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Arc,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+use url::Url;
+
+/// How long a response stays fresh, when the server doesn't say.
+const DEFAULT_TTL: Duration = Duration::from_secs(3600);
+
+/// An on-disk HTTP cache.
+///
+/// Successful responses are stored by URL, and reused as long as they are
+/// fresh according to their `Cache-Control` header (or to the cache TTL). Stale
+/// responses are revalidated with `If-None-Match`/`If-Modified-Since` when
+/// possible.
+///
+/// Images are not cached by default, they are only downloaded once anyway.
+pub struct HttpCache {
+    /// Cache directory.
+    dir: PathBuf,
+
+    /// How long a response stays fresh, when the server doesn't say.
+    ttl: Duration,
+
+    /// Whether images are cached.
+    images: bool,
+}
+
+impl HttpCache {
+    /// Open the cache stored in `dir`, creating it if needed.
+    pub fn new(dir: &Path) -> Result<Self> {
+        crate::fs::mkdir_p(dir)?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            ttl: DEFAULT_TTL,
+            images: false,
+        })
+    }
+
+    /// Set how long a response stays fresh, when the server doesn't say.
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Cache images as well.
+    #[must_use]
+    pub const fn with_images(mut self, images: bool) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Test if the response to `request` can be cached.
+    fn accepts(&self, request: &Request) -> bool {
+        // The response to a form submission depends on the form.
+        if request.body().is_some() {
+            return false;
+        }
+
+        self.images
+            || !request
+                .header("accept")
+                .map_or(false, |accept| accept.starts_with("image/"))
+    }
+
+    /// Load the cached response for `url`, if any.
+    fn load(&self, url: &Url) -> Option<Entry> {
+        let path = self.path_for(url);
+        if !path.exists() {
+            return None;
+        }
+
+        match Entry::load(&path) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                log::warn!("ignoring cached response for {}: {}", url, err);
+                None
+            },
+        }
+    }
+
+    /// Store `entry` into the cache.
+    fn store(&self, entry: &Entry) {
+        let path = self.path_for(&entry.url);
+
+        if let Err(err) = entry.save(&path) {
+            log::warn!("cannot cache response for {}: {}", entry.url, err);
+        }
+    }
+
+    /// Return the path of the cache entry for `url`.
+    fn path_for(&self, url: &Url) -> PathBuf {
+        let mut path = self.dir.clone();
+        path.push(format!(
+            "{:x}.json",
+            Sha256::digest(url.as_str().as_bytes())
+        ));
+        path
+    }
+}
+
+/// A transport caching the responses of another one.
+pub(crate) struct CachingTransport {
+    /// Transport doing the actual work.
+    inner: Arc<dyn Transport>,
+
+    /// Where responses are cached.
+    cache: Arc<HttpCache>,
+}
+
+impl CachingTransport {
+    /// Cache the responses of `inner` into `cache`.
+    pub(crate) fn new(
+        inner: Arc<dyn Transport>,
+        cache: Arc<HttpCache>,
+    ) -> Self {
+        Self { inner, cache }
+    }
+}
+
+impl Transport for CachingTransport {
+    fn send(&self, request: &Request) -> Result<Response> {
+        if !self.cache.accepts(request) {
+            return self.inner.send(request);
+        }
+
+        let cached = self.cache.load(&request.url);
+        let mut request = request.clone();
+        if let Some(ref entry) = cached {
+            if entry.is_fresh(self.cache.ttl) {
+                log::debug!("using cached response for {}", request.url);
+                return Ok(entry.response.clone());
+            }
+            // Stale, ask the server if it's still valid.
+            if let Some(etag) = entry.response.header("etag") {
+                request = request.with_header("If-None-Match", etag);
+            }
+            if let Some(date) = entry.response.header("last-modified") {
+                request = request.with_header("If-Modified-Since", date);
+            }
+        }
+
+        let response = self.inner.send(&request)?;
+
+        if let Some(mut entry) = cached {
+            if response.status == 304 {
+                log::debug!("cached response for {} is still valid", entry.url);
+                entry.refresh(&response);
+                self.cache.store(&entry);
+                return Ok(entry.response);
+            }
+        }
+        if response.status == 200
+            && !has_directive(&response, "no-store")
+            && challenge::detect(&response).is_none()
+        {
+            self.cache.store(&Entry::new(&request.url, &response));
+        }
+
+        Ok(response)
+    }
+}
+
+/// A cached response.
+struct Entry {
+    /// Response URL.
+    url: Url,
+
+    /// When the response was stored (or last revalidated), in seconds since
+    /// the epoch.
+    stored_at: u64,
+
+    /// The response itself.
+    response: Response,
+}
+
+impl Entry {
+    /// Initialize a cache entry for the response to `url`.
+    fn new(url: &Url, response: &Response) -> Self {
+        Self {
+            url: url.clone(),
+            stored_at: now(),
+            response: response.clone(),
+        }
+    }
+
+    /// Load the cache entry stored at `path`.
+    ///
+    /// The response body is stored next to it, in a `.body` file.
+    fn load(path: &Path) -> Result<Self> {
+        let metadata: Metadata =
+            serde_json::from_slice(&read(path)?).map_err(|err| {
+                Error::Config(format!(
+                    "invalid cache entry {}: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+        let body = read(&path.with_extension("body"))?;
+
+        Ok(Self {
+            url: metadata.url,
+            stored_at: metadata.stored_at,
+            response: Response {
+                status: metadata.status,
+                headers: metadata.headers,
+                body,
+            },
+        })
+    }
+
+    /// Save the cache entry at `path`.
+    fn save(&self, path: &Path) -> Result<()> {
+        let metadata = Metadata {
+            url: self.url.clone(),
+            stored_at: self.stored_at,
+            status: self.response.status,
+            headers: self.response.headers.clone(),
+        };
+        let json = serde_json::to_vec_pretty(&metadata).map_err(|err| {
+            Error::Config(format!("invalid cache entry: {}", err))
+        })?;
+
+        crate::fs::atomic_save(
+            &path.with_extension("body"),
+            &self.response.body,
+        )?;
+        crate::fs::atomic_save(path, &json)
+    }
+
+    /// Test if the cached response can be used without revalidation.
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        if has_directive(&self.response, "no-cache") {
+            return false;
+        }
+        let lifetime = max_age(&self.response).unwrap_or(ttl);
+
+        now().saturating_sub(self.stored_at) < lifetime.as_secs()
+    }
+
+    /// Refresh the entry from a `304 Not Modified` response.
+    fn refresh(&mut self, response: &Response) {
+        for &(ref name, ref value) in &response.headers {
+            self.response
+                .headers
+                .retain(|&(ref header, _)| !header.eq_ignore_ascii_case(name));
+            self.response.headers.push((name.clone(), value.clone()));
+        }
+        self.stored_at = now();
+    }
+}
+
+/// Metadata of a cached response (the body is stored separately).
+#[derive(Deserialize, Serialize)]
+struct Metadata {
+    url: Url,
+    stored_at: u64,
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+/// Return the directives of the `Cache-Control` header of `response`.
+fn cache_control(response: &Response) -> impl Iterator<Item = String> + '_ {
+    response
+        .header("cache-control")
+        .unwrap_or_default()
+        .split(',')
+        .map(|directive| directive.trim().to_ascii_lowercase())
+}
+
+/// Test if the `Cache-Control` header of `response` contains `directive`.
+fn has_directive(response: &Response, directive: &str) -> bool {
+    cache_control(response).any(|candidate| candidate == directive)
+}
+
+/// Return the `max-age` of `response`, if any.
+fn max_age(response: &Response) -> Option<Duration> {
+    cache_control(response).find_map(|directive| {
+        directive
+            .strip_prefix("max-age=")
+            .and_then(|secs| secs.parse().ok())
+            .map(Duration::from_secs)
+    })
+}
+
+/// Return the current time, in seconds since the epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Read the whole file at `path`.
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|err| {
+        Error::Filesystem {
+            operation: "read",
+            target: path.to_path_buf(),
+            source: err,
+        }
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryTransport;
+
+    const URL: &str = "https://example.com/series/42";
+
+    /// Cache the responses of a fresh in-memory transport.
+    fn setup(
+        name: &str,
+        ttl: Duration,
+    ) -> (Arc<MemoryTransport>, CachingTransport) {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("hyraigne-cache-{}-{}", name, std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).expect("clean cache directory");
+        }
+        let cache = HttpCache::new(&dir).unwrap().with_ttl(ttl);
+        let inner = Arc::new(MemoryTransport::new());
+        let transport = CachingTransport::new(
+            Arc::clone(&inner) as Arc<dyn Transport>,
+            Arc::new(cache),
+        );
+
+        (inner, transport)
+    }
+
+    #[test]
+    fn test_fresh_response() {
+        let (inner, transport) = setup("fresh", Duration::from_secs(0));
+        let url = Url::parse(URL).unwrap();
+        inner.insert(
+            &url,
+            Response::new(200, "<html></html>")
+                .with_header("Cache-Control", "public, max-age=60"),
+        );
+        let request = Request::get(&url);
+
+        transport.send(&request).unwrap();
+        let response = transport.send(&request).unwrap();
+
+        assert_eq!(response.body(), b"<html></html>");
+        assert_eq!(inner.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_revalidation() {
+        let (inner, transport) = setup("revalidation", Duration::from_secs(0));
+        let url = Url::parse(URL).unwrap();
+        inner.insert(
+            &url,
+            Response::new(200, "<html></html>").with_header("ETag", "\"42\""),
+        );
+        inner.insert(&url, Response::new(304, ""));
+        let request = Request::get(&url);
+
+        transport.send(&request).unwrap();
+        let response = transport.send(&request).unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body(), b"<html></html>");
+        assert_eq!(inner.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_no_store() {
+        let (inner, transport) = setup("no-store", Duration::from_secs(60));
+        let url = Url::parse(URL).unwrap();
+        inner.insert(
+            &url,
+            Response::new(200, "<html></html>")
+                .with_header("Cache-Control", "no-store"),
+        );
+        let request = Request::get(&url);
+
+        transport.send(&request).unwrap();
+        transport.send(&request).unwrap();
+
+        assert_eq!(inner.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_images_are_not_cached() {
+        let (inner, transport) = setup("images", Duration::from_secs(60));
+        let url = Url::parse(URL).unwrap();
+        inner.insert(&url, Response::new(200, "GIF89a"));
+        let request = Request::get(&url).with_header("accept", "image/*");
+
+        transport.send(&request).unwrap();
+        transport.send(&request).unwrap();
+
+        assert_eq!(inner.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_forms_are_not_cached() {
+        let (inner, transport) = setup("forms", Duration::from_secs(60));
+        let url = Url::parse(URL).unwrap();
+        inner.insert(&url, Response::new(200, "<li></li>"));
+        let request = Request::post(&url, &[("action", "list")]);
+
+        transport.send(&request).unwrap();
+        transport.send(&request).unwrap();
+
+        assert_eq!(request.method(), "POST");
+        assert_eq!(request.body(), Some(&b"action=list"[..]));
+        assert_eq!(inner.requests().len(), 2);
+    }
+}
+
+// }}}
@@ -0,0 +1,275 @@
+use super::{
+    MemoryTransport,
+    Request,
+    Response,
+    Transport,
+};
+use crate::{
+    Error,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
+};
+use url::Url;
+
+/// A cassette, where HTTP interactions are recorded to be replayed later.
+///
+/// Each interaction is stored in the cassette directory as two files, named
+/// after its sequence number: `<n>.json` for the request (method, URL, headers
+/// and body) and the response status and headers, and `<n>.body` for the raw
+/// response body.
+///
+/// Beware that recorded headers may contain sensitive data (e.g. cookies).
+pub struct Cassette {
+    /// Cassette directory.
+    dir: PathBuf,
+
+    /// Sequence number of the next interaction.
+    next: AtomicUsize,
+}
+
+impl Cassette {
+    /// Open the cassette stored in `dir` for recording, creating it if needed.
+    ///
+    /// New interactions are appended to the existing ones, if any.
+    pub fn create(dir: &Path) -> Result<Self> {
+        crate::fs::mkdir_p(dir)?;
+        let next = list_interactions(dir)?.len();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            next: AtomicUsize::new(next),
+        })
+    }
+
+    /// Load the interactions recorded in `dir`, to replay them.
+    ///
+    /// Interactions are replayed in the order they were recorded, requests that
+    /// weren't recorded (same method, URL and body) get a `404 Not Found`.
+    pub fn replay(dir: &Path) -> Result<MemoryTransport> {
+        let transport = MemoryTransport::new();
+
+        for path in list_interactions(dir)? {
+            let interaction: Interaction =
+                serde_json::from_slice(&read(&path)?).map_err(|err| {
+                    Error::Config(format!(
+                        "invalid interaction {}: {}",
+                        path.display(),
+                        err
+                    ))
+                })?;
+            let body = read(&path.with_extension("body"))?;
+
+            transport.insert_exact(
+                &interaction.method,
+                &interaction.url,
+                interaction.request_body.as_ref().map(String::as_bytes),
+                Response {
+                    status: interaction.status,
+                    headers: interaction.response_headers,
+                    body,
+                },
+            );
+        }
+
+        Ok(transport)
+    }
+
+    /// Record an interaction.
+    fn record(&self, request: &Request, response: &Response) -> Result<()> {
+        let id = self.next.fetch_add(1, Ordering::SeqCst);
+        let mut path = self.dir.clone();
+        path.push(format!("{:06}.json", id));
+
+        let interaction = Interaction {
+            method: request.method().to_owned(),
+            url: request.url.clone(),
+            request_headers: request.headers.clone(),
+            // Only forms are sent, so the body is text.
+            request_body: request
+                .body()
+                .map(|body| String::from_utf8_lossy(body).into_owned()),
+            status: response.status,
+            response_headers: response.headers.clone(),
+        };
+        let json = serde_json::to_vec_pretty(&interaction).map_err(|err| {
+            Error::Config(format!("invalid interaction: {}", err))
+        })?;
+
+        crate::fs::atomic_save(&path.with_extension("body"), &response.body)?;
+        crate::fs::atomic_save(&path, &json)
+    }
+}
+
+/// A transport recording every interaction into a cassette.
+pub(crate) struct RecordingTransport {
+    /// Transport doing the actual work.
+    inner: Arc<dyn Transport>,
+
+    /// Where interactions are recorded.
+    cassette: Arc<Cassette>,
+}
+
+impl RecordingTransport {
+    /// Record the interactions of `inner` into `cassette`.
+    pub(crate) fn new(
+        inner: Arc<dyn Transport>,
+        cassette: Arc<Cassette>,
+    ) -> Self {
+        Self { inner, cassette }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: &Request) -> Result<Response> {
+        let response = self.inner.send(request)?;
+
+        self.cassette.record(request, &response)?;
+
+        Ok(response)
+    }
+}
+
+/// A recorded interaction (minus the response body, stored separately).
+#[derive(Deserialize, Serialize)]
+struct Interaction {
+    method: String,
+    url: Url,
+    request_headers: Vec<(String, String)>,
+    request_body: Option<String>,
+    status: u16,
+    response_headers: Vec<(String, String)>,
+}
+
+/// List the interactions recorded in `dir`, in order.
+fn list_interactions(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).map_err(|err| {
+        Error::Filesystem {
+            operation: "list",
+            target: dir.to_path_buf(),
+            source: err,
+        }
+    })?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| {
+                Error::Filesystem {
+                    operation: "list",
+                    target: dir.to_path_buf(),
+                    source: err,
+                }
+            })?
+            .path();
+        if path.extension().map_or(false, |ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Read the whole file at `path`.
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|err| {
+        Error::Filesystem {
+            operation: "read",
+            target: path.to_path_buf(),
+            source: err,
+        }
+    })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("hyraigne-cassette-{}", std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).expect("clean cassette directory");
+        }
+        let url = Url::parse("https://example.com/series/42").unwrap();
+        let inner = Arc::new(MemoryTransport::new());
+        inner.insert(&url, Response::new(503, "Service Unavailable"));
+        inner.insert(
+            &url,
+            Response::new(200, "<html></html>").with_header("ETag", "42"),
+        );
+
+        let cassette = Arc::new(Cassette::create(&dir).unwrap());
+        let recorder = RecordingTransport::new(inner, cassette);
+        let request = Request::get(&url);
+        recorder.send(&request).unwrap();
+        recorder.send(&request).unwrap();
+
+        let replay = Cassette::replay(&dir).unwrap();
+        let first = replay.send(&request).unwrap();
+        let second = replay.send(&request).unwrap();
+
+        assert_eq!(first.status(), 503);
+        assert_eq!(second.status(), 200);
+        assert_eq!(second.header("etag"), Some("42"));
+        assert_eq!(second.body(), b"<html></html>");
+    }
+
+    #[test]
+    fn test_replay_by_method_and_body() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("hyraigne-cassette-post-{}", std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).expect("clean cassette directory");
+        }
+        let url = Url::parse("https://example.com/ajax").unwrap();
+        let form = |body: &str| {
+            Request {
+                body: Some(body.as_bytes().to_vec()),
+                ..Request::get(&url)
+            }
+        };
+        let (first, second) = (form("id=1"), form("id=2"));
+        let inner = Arc::new(MemoryTransport::new());
+        inner.insert_exact("POST", &url, first.body(), Response::new(200, "1"));
+        inner.insert_exact(
+            "POST",
+            &url,
+            second.body(),
+            Response::new(200, "2"),
+        );
+
+        let cassette = Arc::new(Cassette::create(&dir).unwrap());
+        let recorder = RecordingTransport::new(inner, cassette);
+        recorder.send(&first).unwrap();
+        recorder.send(&second).unwrap();
+
+        let replay = Cassette::replay(&dir).unwrap();
+
+        assert_eq!(replay.send(&second).unwrap().body(), b"2");
+        assert_eq!(replay.send(&first).unwrap().body(), b"1");
+        assert_eq!(replay.send(&Request::get(&url)).unwrap().status(), 404);
+    }
+}
+
+// }}}
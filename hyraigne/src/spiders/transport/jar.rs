@@ -0,0 +1,333 @@
+use crate::{
+    Error,
+    Result,
+};
+use cookie_store::{
+    Cookie,
+    CookieStore,
+};
+use std::{
+    convert::TryFrom,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+use url::Url;
+
+/// Header of the Netscape cookie file format.
+const HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// A cookie jar, shared by the web spiders and persisted on disk.
+///
+/// Cookies are stored in the Netscape format (`cookies.txt`), as exported by
+/// most browsers extensions.
+pub struct CookieJar {
+    /// Where the cookies are persisted, if any.
+    path: Option<PathBuf>,
+
+    /// The cookies.
+    store: Mutex<CookieStore>,
+}
+
+impl CookieJar {
+    /// Initialize an empty, in-memory, cookie jar.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            store: Mutex::new(CookieStore::default()),
+        }
+    }
+
+    /// Open the cookie jar stored at `path`, if it exists.
+    ///
+    /// Cookies are written back to `path` by `CookieJar::save`.
+    pub fn open(path: &Path) -> Result<Self> {
+        let jar = Self {
+            path: Some(path.to_path_buf()),
+            store: Mutex::new(CookieStore::default()),
+        };
+        if path.exists() {
+            jar.import(path)?;
+        }
+
+        Ok(jar)
+    }
+
+    /// Import the cookies of the Netscape cookie file at `path`.
+    ///
+    /// Return the number of imported cookies.
+    pub fn import(&self, path: &Path) -> Result<usize> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            Error::Filesystem {
+                operation: "read",
+                target: path.to_path_buf(),
+                source: err,
+            }
+        })?;
+
+        let mut store = self.lock();
+        let mut count = 0;
+        for (i, line) in content.lines().enumerate() {
+            let inserted = import_line(&mut store, line).map_err(|msg| {
+                Error::Config(format!("{}:{}: {}", path.display(), i + 1, msg))
+            })?;
+            count += usize::from(inserted);
+        }
+
+        Ok(count)
+    }
+
+    /// Write the cookies back to the file the jar was opened from, if any.
+    ///
+    /// Expired cookies are dropped.
+    pub fn save(&self) -> Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        let mut content = format!("{}\n", HEADER);
+        for cookie in self.lock().iter_unexpired() {
+            if let Some(line) = export_cookie(cookie) {
+                content.push_str(&line);
+                content.push('\n');
+            }
+        }
+
+        crate::fs::atomic_save(path, content.as_bytes())
+    }
+
+    /// Copy the cookies of the jar into `store`.
+    pub(crate) fn fill(&self, store: &mut CookieStore) {
+        copy_cookies(&self.lock(), store);
+    }
+
+    /// Copy the cookies of `store` into the jar.
+    pub(crate) fn absorb(&self, store: &CookieStore) {
+        copy_cookies(store, &mut self.lock());
+    }
+
+    /// Lock the cookie store.
+    fn lock(&self) -> MutexGuard<'_, CookieStore> {
+        // Worst case, a cookie update was lost.
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for CookieJar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copy the unexpired cookies of `from` into `to`.
+fn copy_cookies(from: &CookieStore, to: &mut CookieStore) {
+    for cookie in from.iter_unexpired() {
+        let url = match url_for(cookie) {
+            Some(url) => url,
+            None => continue,
+        };
+        if let Err(err) = to.insert(cookie.clone(), &url) {
+            log::warn!("cannot copy cookie {}: {}", cookie.name(), err);
+        }
+    }
+}
+
+/// Return an URL the `cookie` could have been set from.
+fn url_for(cookie: &Cookie<'_>) -> Option<Url> {
+    let domain = cookie.domain.as_cow()?;
+
+    Url::parse(&format!("https://{}{}", domain, String::from(&cookie.path)))
+        .ok()
+}
+
+/// Import a line of a Netscape cookie file into `store`.
+///
+/// Return whether a cookie was imported (comments, blank lines and expired
+/// cookies are skipped).
+fn import_line(
+    store: &mut CookieStore,
+    line: &str,
+) -> std::result::Result<bool, String> {
+    // Some browsers flag HTTP-only cookies with a "comment" prefix.
+    let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+        Some(line) => (line, true),
+        None => (line, false),
+    };
+    if line.trim().is_empty() || line.starts_with('#') {
+        return Ok(false);
+    }
+
+    let fields = line.split('\t').collect::<Vec<_>>();
+    let (domain, subdomains, path, secure, expires, name, value) =
+        match fields.as_slice() {
+            &[domain, subdomains, path, secure, expires, name, value] => {
+                (domain, subdomains, path, secure, expires, name, value)
+            },
+            _ => return Err("expected 7 tab-separated fields".to_owned()),
+        };
+    let expires = expires
+        .parse::<i64>()
+        .map_err(|err| format!("invalid expiration date: {}", err))?;
+
+    let host = domain.trim_start_matches('.');
+    let mut cookie = format!("{}={}; Path={}", name, value, path);
+    if subdomains.eq_ignore_ascii_case("TRUE") {
+        cookie.push_str(&format!("; Domain={}", host));
+    }
+    if secure.eq_ignore_ascii_case("TRUE") {
+        cookie.push_str("; Secure");
+    }
+    if http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    // 0 means a session cookie.
+    if expires != 0 {
+        let remaining = expires - now();
+        if remaining <= 0 {
+            return Ok(false);
+        }
+        cookie.push_str(&format!("; Max-Age={}", remaining));
+    }
+
+    let url = Url::parse(&format!("https://{}{}", host, path))
+        .map_err(|err| format!("invalid domain or path: {}", err))?;
+    store
+        .parse(&cookie, &url)
+        .map_err(|err| format!("invalid cookie {}: {}", name, err))?;
+
+    Ok(true)
+}
+
+/// Export `cookie` as a line of a Netscape cookie file.
+fn export_cookie(cookie: &Cookie<'_>) -> Option<String> {
+    // The stored cookie keeps its domain and expiration date to itself, but
+    // converting it back gives the matching attributes (`Domain` only when
+    // the cookie is sent to the subdomains).
+    let attributes = cookie::Cookie::from(cookie.clone());
+    let (domain, subdomains) = match attributes.domain() {
+        Some(domain) => (format!(".{}", domain), "TRUE"),
+        None => (cookie.domain.as_cow()?.into_owned(), "FALSE"),
+    };
+    // 0 means a session cookie.
+    let expires = attributes
+        .expires()
+        .and_then(cookie::Expiration::datetime)
+        .map_or(0, |date| date.unix_timestamp());
+
+    Some(format!(
+        "{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        if cookie.http_only().unwrap_or(false) {
+            "#HttpOnly_"
+        } else {
+            ""
+        },
+        domain,
+        subdomains,
+        String::from(&cookie.path),
+        if cookie.secure().unwrap_or(false) {
+            "TRUE"
+        } else {
+            "FALSE"
+        },
+        expires,
+        cookie.name(),
+        cookie.value()
+    ))
+}
+
+/// Return the current time, in seconds since the epoch.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOKIES: &str = "# Netscape HTTP Cookie File
+.webtoons.com\tTRUE\t/\tFALSE\t0\tpagGDPR\ttrue
+#HttpOnly_mangakakalot.com\tFALSE\t/\tTRUE\t4102444800\tsession\t42
+mangakakalot.com\tFALSE\t/\tFALSE\t1\texpired\tyes
+";
+
+    #[test]
+    fn test_import_and_save() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("hyraigne-jar-{}", std::process::id()));
+        crate::fs::mkdir_p(&dir).unwrap();
+        let mut input = dir.clone();
+        input.push("cookies.txt");
+        fs::write(&input, COOKIES).unwrap();
+        let mut output = dir.clone();
+        output.push("jar.txt");
+        if output.exists() {
+            fs::remove_file(&output).unwrap();
+        }
+
+        let jar = CookieJar::open(&output).unwrap();
+        assert_eq!(jar.import(&input).unwrap(), 2);
+        jar.save().unwrap();
+
+        let saved = fs::read_to_string(&output).unwrap();
+        let mut lines = saved.lines().collect::<Vec<_>>();
+        lines.sort_unstable();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "# Netscape HTTP Cookie File");
+        // Expiration date may be off by a second.
+        assert!(lines[1]
+            .starts_with("#HttpOnly_mangakakalot.com\tFALSE\t/\tTRUE\t410244"));
+        assert!(lines[1].ends_with("\tsession\t42"));
+        assert_eq!(lines[2], ".webtoons.com\tTRUE\t/\tFALSE\t0\tpagGDPR\ttrue");
+    }
+
+    #[test]
+    fn test_fill_store() {
+        let jar = CookieJar::new();
+        jar.lock()
+            .parse(
+                "session=42; Path=/",
+                &Url::parse("https://mangakakalot.com/").unwrap(),
+            )
+            .unwrap();
+        let mut store = CookieStore::default();
+
+        jar.fill(&mut store);
+
+        let url = Url::parse("https://mangakakalot.com/manga/42").unwrap();
+        assert_eq!(
+            store
+                .get_request_cookies(&url)
+                .map(|cookie| (cookie.name(), cookie.value()))
+                .collect::<Vec<_>>(),
+            vec![("session", "42")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let mut store = CookieStore::default();
+
+        assert!(import_line(&mut store, "example.com\tFALSE\t/").is_err());
+    }
+}
+
+// }}}
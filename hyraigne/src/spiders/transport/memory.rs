@@ -0,0 +1,150 @@
+#[cfg(feature = "async")]
+use super::AsyncTransport;
+use super::{
+    Request,
+    Response,
+    Transport,
+};
+use crate::Result;
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::{
+        Mutex,
+        PoisonError,
+    },
+};
+use url::Url;
+
+/// An in-memory transport, serving canned responses keyed by URL.
+///
+/// Requests for an unknown URL are answered with a `404 Not Found`.
+///
+/// Responses can also be restricted to a given method and body (e.g. when
+/// replaying a cassette), those take precedence over the ones served for any
+/// request to the URL.
+///
+/// # Example
+///
+/// ```
+/// use hyraigne::{MemoryTransport, Options, Response};
+/// use std::sync::Arc;
+/// use url::Url;
+///
+/// let url = Url::parse("https://mangakakalot.com/manga/tower_of_god").unwrap();
+/// let transport = Arc::new(MemoryTransport::new());
+/// transport.insert(&url, Response::new(200, "<html></html>"));
+///
+/// let opts = Options::new(0, 0, ".".into()).with_transport(transport.clone());
+/// ```
+#[derive(Default)]
+pub struct MemoryTransport {
+    /// Responses to serve, by URL or by request key.
+    responses: Mutex<HashMap<String, VecDeque<Response>>>,
+
+    /// URLs requested so far.
+    requests: Mutex<Vec<Url>>,
+}
+
+impl MemoryTransport {
+    /// Initialize a transport without any response.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `response` for `url`.
+    ///
+    /// When several responses are registered for the same URL, they are served
+    /// in order, the last one being served again for every subsequent request
+    /// (e.g. to simulate a transient error followed by a success).
+    pub fn insert(&self, url: &Url, response: Response) {
+        self.push(url.as_str().to_owned(), response);
+    }
+
+    /// Serve `response` for the `method` requests to `url` sending `body`.
+    pub(crate) fn insert_exact(
+        &self,
+        method: &str,
+        url: &Url,
+        body: Option<&[u8]>,
+        response: Response,
+    ) {
+        self.push(request_key(method, url, body), response);
+    }
+
+    /// Queue `response` under `key`.
+    fn push(&self, key: String, response: Response) {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key)
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Return the URLs requested so far, in order.
+    #[must_use]
+    pub fn requests(&self) -> Vec<Url> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: &Request) -> Result<Response> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request.url().clone());
+
+        let mut responses = self
+            .responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let key = request_key(request.method(), request.url(), request.body());
+        let response = if responses.contains_key(&key) {
+            responses.get_mut(&key)
+        } else {
+            responses.get_mut(request.url().as_str())
+        }
+        .and_then(|responses| {
+            if responses.len() > 1 {
+                responses.pop_front()
+            } else {
+                responses.front().cloned()
+            }
+        });
+
+        Ok(response.unwrap_or_else(|| {
+            log::warn!("no response for {}", request.url().as_str());
+            Response::new(404, "Not Found")
+        }))
+    }
+}
+
+/// Key of the responses served only for a given method, URL and body.
+fn request_key(method: &str, url: &Url, body: Option<&[u8]>) -> String {
+    format!(
+        "{} {} {:x}",
+        method,
+        url.as_str(),
+        Sha256::digest(body.unwrap_or_default())
+    )
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncTransport for MemoryTransport {
+    async fn send(&self, request: &Request) -> Result<Response> {
+        Transport::send(self, request)
+    }
+}
@@ -0,0 +1,201 @@
+//! HTTP transports, used by the web spiders to send their requests.
+
+mod agent;
+#[cfg(feature = "async")]
+mod async_agent;
+#[cfg(feature = "async")]
+mod blocking;
+mod cache;
+mod cassette;
+mod jar;
+mod memory;
+
+pub(crate) use agent::AgentTransport;
+#[cfg(feature = "async")]
+pub(crate) use async_agent::AsyncAgentTransport;
+#[cfg(feature = "async")]
+pub(crate) use blocking::BlockingTransport;
+pub(crate) use cache::CachingTransport;
+pub use cache::HttpCache;
+pub use cassette::Cassette;
+pub(crate) use cassette::RecordingTransport;
+pub use jar::CookieJar;
+pub use memory::MemoryTransport;
+
+use crate::Result;
+use url::{
+    form_urlencoded,
+    Url,
+};
+
+/// Send HTTP requests on behalf of the web spiders.
+///
+/// The default implementation relies on `ureq`, others can be provided through
+/// `Options::with_transport` (e.g. to serve canned responses in tests).
+pub trait Transport: Send + Sync {
+    /// Send `request` and return the response.
+    ///
+    /// HTTP errors (4xx, 5xx, …) must be returned as a response, retries are
+    /// handled by the caller: an error is only expected when no response could
+    /// be obtained at all.
+    fn send(&self, request: &Request) -> Result<Response>;
+}
+
+/// Send HTTP requests on behalf of the async web spiders.
+///
+/// The default implementation relies on `reqwest`, others can be provided
+/// through `Options::with_async_transport`.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncTransport: Send + Sync {
+    /// Send `request` and return the response.
+    ///
+    /// Same contract as `Transport::send`: HTTP errors must be returned as a
+    /// response.
+    async fn send(&self, request: &Request) -> Result<Response>;
+}
+
+/// A `GET` request, or a `POST` request submitting a form.
+#[derive(Clone, Debug)]
+pub struct Request {
+    /// Requested URL.
+    pub(crate) url: Url,
+
+    /// Request headers.
+    pub(crate) headers: Vec<(String, String)>,
+
+    /// Request body, for a `POST` request.
+    pub(crate) body: Option<Vec<u8>>,
+}
+
+impl Request {
+    /// Initialize a request for `url`.
+    pub(crate) fn get(url: &Url) -> Self {
+        Self {
+            url: url.clone(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Initialize a request submitting `form` to `url`.
+    pub(crate) fn post(url: &Url, form: &[(&str, &str)]) -> Self {
+        let body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish();
+
+        Self {
+            url: url.clone(),
+            headers: Vec::new(),
+            body: Some(body.into_bytes()),
+        }
+        .with_header("content-type", "application/x-www-form-urlencoded")
+    }
+
+    /// Set the header `name` to `value`.
+    pub(crate) fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// HTTP method: `POST` if there is a body, `GET` otherwise.
+    #[must_use]
+    pub const fn method(&self) -> &'static str {
+        if self.body.is_some() {
+            "POST"
+        } else {
+            "GET"
+        }
+    }
+
+    /// Requested URL.
+    #[must_use]
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Value of the header `name` (case-insensitive), if any.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Request headers.
+    #[must_use]
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Request body, if any.
+    #[must_use]
+    pub fn body(&self) -> Option<&[u8]> {
+        self.body.as_deref()
+    }
+}
+
+/// An HTTP response.
+#[derive(Clone, Debug)]
+pub struct Response {
+    /// HTTP status code.
+    pub(crate) status: u16,
+
+    /// Response headers.
+    pub(crate) headers: Vec<(String, String)>,
+
+    /// Response body.
+    pub(crate) body: Vec<u8>,
+}
+
+impl Response {
+    /// Initialize a response with the given status code and body.
+    #[must_use]
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Set the header `name` to `value`.
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// HTTP status code.
+    #[must_use]
+    pub const fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Value of the header `name` (case-insensitive), if any.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Response headers.
+    #[must_use]
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Response body.
+    #[must_use]
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+/// Return the value of the header `name` (case-insensitive), if any.
+fn find_header<'a>(
+    headers: &'a [(String, String)],
+    name: &str,
+) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|&&(ref header, _)| header.eq_ignore_ascii_case(name))
+        .map(|&(_, ref value)| value.as_str())
+}
@@ -0,0 +1,111 @@
+//! Helpers for the end-to-end tests of the web spiders.
+
+use crate::{
+    Filter,
+    MemoryTransport,
+    Options,
+    Page,
+    Response,
+    Site,
+};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+};
+use url::Url;
+
+/// Load the content of `testdata/<path>`.
+pub(crate) fn fixture(path: &str) -> Vec<u8> {
+    let mut fullpath = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fullpath.push("testdata");
+    fullpath.push(path);
+
+    std::fs::read(&fullpath).expect("test data")
+}
+
+/// Serve the fixture at `path` for `url`.
+pub(crate) fn serve(transport: &MemoryTransport, url: &str, path: &str) {
+    transport.insert(
+        &Url::parse(url).expect("valid URL"),
+        Response::new(200, fixture(path)),
+    );
+}
+
+/// Build options relying on `transport`, with a fresh output directory.
+pub(crate) fn options(transport: &Arc<MemoryTransport>, name: &str) -> Options {
+    let mut output = std::env::temp_dir();
+    output.push(format!("hyraigne-{}-{}", name, std::process::id()));
+    // Start from a clean slate.
+    if output.exists() {
+        std::fs::remove_dir_all(&output).expect("clean output directory");
+    }
+
+    Options::new(0, 1, output)
+        .with_transport(Arc::clone(transport) as Arc<dyn crate::Transport>)
+}
+
+/// Scrape the pages of the chapter 330 of Higanjima, served by `transport`
+/// from the `mangakakalot.com` test data.
+///
+/// The series and its chapters are leaked, for the pages to outlive the call.
+pub(crate) fn mangakakalot_pages(
+    site: &impl Site,
+    transport: &MemoryTransport,
+) -> Vec<Page<'static>> {
+    serve_mangakakalot(transport);
+    let url = Url::parse(MANGAKAKALOT_SERIES).expect("valid URL");
+
+    let series = Box::leak(Box::new(site.get_series(&url).expect("series")));
+    let filter = Filter::new(330..=330, None, Vec::new());
+    let chapters = site.get_chapters(series, filter).expect("chapters");
+
+    site.get_pages(&Box::leak(chapters.into_boxed_slice())[0])
+        .expect("pages")
+}
+
+/// Async version of `mangakakalot_pages`.
+#[cfg(feature = "async")]
+pub(crate) async fn mangakakalot_pages_async(
+    site: &impl crate::AsyncSite,
+    transport: &MemoryTransport,
+) -> Vec<Page<'static>> {
+    serve_mangakakalot(transport);
+    let url = Url::parse(MANGAKAKALOT_SERIES).expect("valid URL");
+
+    let series =
+        Box::leak(Box::new(site.get_series(&url).await.expect("series")));
+    let filter = Filter::new(330..=330, None, Vec::new());
+    let chapters = site.get_chapters(series, filter).await.expect("chapters");
+
+    site.get_pages(&Box::leak(chapters.into_boxed_slice())[0])
+        .await
+        .expect("pages")
+}
+
+/// Series used by `mangakakalot_pages`.
+const MANGAKAKALOT_SERIES: &str =
+    "https://mangakakalot.com/read-lu8jl158504848312";
+
+/// Serve the pages scraped by `mangakakalot_pages`.
+fn serve_mangakakalot(transport: &MemoryTransport) {
+    serve(
+        transport,
+        MANGAKAKALOT_SERIES,
+        "mangakakalot.com/series.html",
+    );
+    serve(
+        transport,
+        "https://mangakakalot.com/chapter/qrka108781556854403/chapter_330",
+        "mangakakalot.com/chapter.html",
+    );
+}
+
+/// Run `future` to completion, on a single-threaded runtime.
+#[cfg(feature = "async")]
+pub(crate) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime")
+        .block_on(future)
+}
@@ -0,0 +1,111 @@
+use url::Url;
+
+/// Base URLs remapped to other ones (e.g. a mirror, or a local mock server).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BaseUrls {
+    /// Pairs of (original, replacement) base URLs.
+    mappings: Vec<(String, String)>,
+}
+
+impl BaseUrls {
+    /// Remap the URLs starting with `base` to `replacement`.
+    pub(crate) fn insert(&mut self, base: &Url, replacement: &Url) {
+        self.mappings.push((
+            with_trailing_slash(base),
+            with_trailing_slash(replacement),
+        ));
+    }
+
+    /// Return `url` with its base remapped, if it matches one.
+    pub(crate) fn rewrite(&self, url: &Url) -> Url {
+        remap(
+            url,
+            self.mappings.iter().map(|&(ref from, ref to)| (from, to)),
+        )
+    }
+
+    /// Return `url` with its original base restored, if it matches one.
+    pub(crate) fn restore(&self, url: &Url) -> Url {
+        remap(
+            url,
+            self.mappings.iter().map(|&(ref from, ref to)| (to, from)),
+        )
+    }
+}
+
+/// Replace the base of `url` using the first matching mapping.
+fn remap<'a>(
+    url: &Url,
+    mut mappings: impl Iterator<Item = (&'a String, &'a String)>,
+) -> Url {
+    let input = url.as_str();
+
+    mappings
+        .find_map(|(from, to)| {
+            // Also match the base itself, without its trailing slash.
+            let rest = input.strip_prefix(from.as_str()).or_else(|| {
+                if input == from.trim_end_matches('/') {
+                    Some("")
+                } else {
+                    None
+                }
+            })?;
+            Url::parse(&format!("{}{}", to, rest)).ok()
+        })
+        .unwrap_or_else(|| url.clone())
+}
+
+/// Return `url` as a string, ending with a slash.
+fn with_trailing_slash(url: &Url) -> String {
+    let mut url = url.as_str().to_owned();
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    url
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_urls() -> BaseUrls {
+        let mut base_urls = BaseUrls::default();
+        base_urls.insert(
+            &Url::parse("https://api.mangadex.org/v2").unwrap(),
+            &Url::parse("http://127.0.0.1:8080/mangadex/").unwrap(),
+        );
+        base_urls
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let url = Url::parse("https://api.mangadex.org/v2/manga/642").unwrap();
+
+        assert_eq!(
+            base_urls().rewrite(&url).as_str(),
+            "http://127.0.0.1:8080/mangadex/manga/642"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_on_path_boundary() {
+        let url = Url::parse("https://api.mangadex.org/v20/manga/642").unwrap();
+
+        assert_eq!(base_urls().rewrite(&url), url);
+    }
+
+    #[test]
+    fn test_restore() {
+        let url =
+            Url::parse("http://127.0.0.1:8080/mangadex/manga/642").unwrap();
+
+        assert_eq!(
+            base_urls().restore(&url).as_str(),
+            "https://api.mangadex.org/v2/manga/642"
+        );
+    }
+}
+
+// }}}
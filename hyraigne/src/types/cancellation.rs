@@ -0,0 +1,141 @@
+use crate::{
+    Error,
+    Result,
+};
+#[cfg(feature = "async")]
+use std::cmp;
+use std::{
+    sync::{
+        Arc,
+        Condvar,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// How often the async web spiders check the token while sleeping.
+#[cfg(feature = "async")]
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A token to stop the web spiders cooperatively.
+///
+/// Clones share the same state: cancel one (e.g. from a signal handler, or
+/// another thread), and every web spider using another one stops before its
+/// next request with `Error::Cancelled`.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    /// Cancellation flag, and the condition variable to wake up the sleepers.
+    state: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancellationToken {
+    /// Initialize a new, not cancelled, token.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the ongoing (and future) operations.
+    pub fn cancel(&self) {
+        *self.lock() = true;
+        self.state.1.notify_all();
+    }
+
+    /// Test if the token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        *self.lock()
+    }
+
+    /// Return `Error::Cancelled` if the token has been cancelled.
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Sleep for `duration`, unless the token is cancelled in the meantime.
+    pub(crate) fn sleep(&self, duration: Duration) -> Result<()> {
+        let deadline = Instant::now() + duration;
+        let mut cancelled = self.lock();
+
+        while !*cancelled {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            cancelled = self
+                .state
+                .1
+                .wait_timeout(cancelled, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        Err(Error::Cancelled)
+    }
+
+    /// Sleep for `duration` without blocking the thread, unless the token is
+    /// cancelled in the meantime.
+    #[cfg(feature = "async")]
+    pub(crate) async fn sleep_async(&self, duration: Duration) -> Result<()> {
+        let deadline = Instant::now() + duration;
+
+        loop {
+            self.check()?;
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            tokio::time::sleep(cmp::min(deadline - now, POLL_INTERVAL)).await;
+        }
+    }
+
+    /// Lock the cancellation flag.
+    fn lock(&self) -> MutexGuard<'_, bool> {
+        // A plain boolean can't be left in an inconsistent state.
+        self.state.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_sleep() {
+        let token = CancellationToken::new();
+
+        assert!(token.sleep(Duration::from_millis(10)).is_ok());
+        assert!(token.check().is_ok());
+    }
+
+    #[test]
+    fn test_cancel_while_sleeping() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let start = Instant::now();
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            canceller.cancel();
+        });
+        let res = token.sleep(Duration::from_secs(60));
+        handle.join().unwrap();
+
+        assert!(matches!(res, Err(Error::Cancelled)));
+        assert!(start.elapsed() < Duration::from_secs(60));
+        assert!(matches!(token.check(), Err(Error::Cancelled)));
+    }
+}
+
+// }}}
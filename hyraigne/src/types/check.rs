@@ -0,0 +1,158 @@
+use kuchiki::traits::*;
+use regex::Regex;
+
+/// Outcome of a selector (or regexp) of a web spider, run against a document.
+pub struct SelectorCheck {
+    /// Name of the selector (e.g. `series_title`).
+    pub(crate) name: String,
+
+    /// The CSS selector, or regexp, that was run.
+    pub(crate) pattern: String,
+
+    /// Number of matches.
+    pub(crate) matches: usize,
+
+    /// Values extracted from the matches.
+    pub(crate) values: Vec<String>,
+}
+
+impl SelectorCheck {
+    /// Run `selectors` against `html`.
+    ///
+    /// Values are read from `attribute`, or from the text content if `None`.
+    pub(crate) fn run(
+        name: &str,
+        selectors: &kuchiki::Selectors,
+        html: &kuchiki::NodeRef,
+        attribute: Option<&str>,
+    ) -> Self {
+        let elements = selectors
+            .filter(html.inclusive_descendants().elements())
+            .collect::<Vec<_>>();
+
+        Self {
+            name: name.to_owned(),
+            pattern: selectors.to_string(),
+            matches: elements.len(),
+            values: elements
+                .iter()
+                .filter_map(|element| value_of(element, attribute))
+                .collect(),
+        }
+    }
+
+    /// Run `selectors` inside every element of `html` matched by `parent`.
+    ///
+    /// Only the first match inside each parent element is considered.
+    pub(crate) fn run_nested(
+        name: &str,
+        parent: &kuchiki::Selectors,
+        selectors: &kuchiki::Selectors,
+        html: &kuchiki::NodeRef,
+        attribute: Option<&str>,
+    ) -> Self {
+        let elements = parent
+            .filter(html.inclusive_descendants().elements())
+            .filter_map(|parent| {
+                selectors
+                    .filter(parent.as_node().inclusive_descendants().elements())
+                    .next()
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            name: name.to_owned(),
+            pattern: selectors.to_string(),
+            matches: elements.len(),
+            values: elements
+                .iter()
+                .filter_map(|element| value_of(element, attribute))
+                .collect(),
+        }
+    }
+
+    /// Run `regex` against every input.
+    ///
+    /// Values are made of the named capture groups (or of the whole match).
+    pub(crate) fn run_regex<'a>(
+        name: &str,
+        regex: &Regex,
+        inputs: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let values = inputs
+            .into_iter()
+            .filter_map(|input| regex.captures(input))
+            .map(|captures| {
+                let groups = regex
+                    .capture_names()
+                    .flatten()
+                    .filter_map(|group| {
+                        captures.name(group).map(|value| {
+                            format!("{}={}", group, value.as_str())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                if groups.is_empty() {
+                    captures[0].to_owned()
+                } else {
+                    groups.join(", ")
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            name: name.to_owned(),
+            pattern: regex.as_str().to_owned(),
+            matches: values.len(),
+            values,
+        }
+    }
+
+    /// Name of the selector (e.g. `series_title`).
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The CSS selector, or regexp, that was run.
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Number of matches.
+    #[must_use]
+    pub const fn matches(&self) -> usize {
+        self.matches
+    }
+
+    /// Values extracted from the matches.
+    #[must_use]
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+}
+
+/// Read the value of an element, from `attribute` or its text content.
+fn value_of(
+    element: &kuchiki::NodeDataRef<kuchiki::ElementData>,
+    attribute: Option<&str>,
+) -> Option<String> {
+    let value = match attribute {
+        Some(attribute) => {
+            element
+                .attributes
+                .borrow()
+                .get(attribute)
+                .map(str::to_owned)?
+        },
+        None => element.text_contents(),
+    };
+    let value = value.trim();
+
+    if value.is_empty() {
+        return None;
+    }
+    Some(value.to_owned())
+}
@@ -0,0 +1,127 @@
+use super::{
+    Chapter,
+    Series,
+};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use url::Url;
+
+/// Callback receiving the progress events of the web spiders.
+pub type EventHandler = Arc<dyn Fn(&Event) + Send + Sync>;
+
+/// A progress event, emitted by the web spiders.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Event {
+    /// A series was found.
+    SeriesResolved {
+        /// Series title.
+        title: String,
+        /// URL of the series page or endpoint.
+        url: Url,
+    },
+
+    /// A chapter matching the filter was found.
+    ChapterListed {
+        /// Chapter ID.
+        id: f64,
+        /// URL of the chapter page or endpoint.
+        url: Url,
+    },
+
+    /// The download of a chapter started.
+    ChapterStarted {
+        /// Chapter ID.
+        id: f64,
+        /// Number of pages in the chapter.
+        pages: usize,
+    },
+
+    /// A page was downloaded.
+    PageDownloaded {
+        /// ID of the chapter containing the page.
+        chapter: f64,
+        /// Page number.
+        page: u16,
+        /// Where the page was saved.
+        path: PathBuf,
+        /// Size of the page, in bytes.
+        bytes: usize,
+        /// How long the download took.
+        duration: Duration,
+    },
+
+    /// A page was skipped, because it was already downloaded.
+    PageSkipped {
+        /// ID of the chapter containing the page.
+        chapter: f64,
+        /// Page number.
+        page: u16,
+        /// Where the page is saved.
+        path: PathBuf,
+    },
+
+    /// Every page of a chapter was downloaded.
+    ChapterFinished {
+        /// Chapter ID.
+        id: f64,
+    },
+
+    /// The download of a chapter failed.
+    ///
+    /// The error itself is also returned to the caller.
+    Error {
+        /// ID of the chapter being downloaded.
+        chapter: f64,
+        /// Kind of error (see `Error::kind`).
+        kind: &'static str,
+        /// URL involved in the error, if any.
+        url: Option<String>,
+        /// Error message.
+        message: String,
+    },
+}
+
+/// Dispatch the progress events to the event handler, if any.
+#[derive(Clone, Default)]
+pub(crate) struct Events {
+    /// Event handler.
+    handler: Option<EventHandler>,
+}
+
+impl Events {
+    /// Dispatch the events to `handler`.
+    pub(crate) fn new(handler: EventHandler) -> Self {
+        Self {
+            handler: Some(handler),
+        }
+    }
+
+    /// Send `event` to the event handler.
+    pub(crate) fn emit(&self, event: &Event) {
+        if let Some(ref handler) = self.handler {
+            handler(event);
+        }
+    }
+
+    /// Signal that `series` was found.
+    pub(crate) fn series_resolved(&self, series: &Series) {
+        self.emit(&Event::SeriesResolved {
+            title: series.title.clone(),
+            url: series.url.clone(),
+        });
+    }
+
+    /// Signal that `chapters` were found.
+    pub(crate) fn chapters_listed(&self, chapters: &[Chapter<'_>]) {
+        for chapter in chapters {
+            self.emit(&Event::ChapterListed {
+                id: chapter.id,
+                url: chapter.url.clone(),
+            });
+        }
+    }
+}
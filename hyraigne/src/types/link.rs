@@ -0,0 +1,12 @@
+/// What a URL points to, on a given website.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkKind {
+    /// A series (its main page).
+    Series,
+
+    /// A chapter of a series.
+    Chapter,
+
+    /// A single page of a chapter.
+    Page,
+}
@@ -0,0 +1,9 @@
+/// A listing of series, as provided by a website.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Listing {
+    /// Recently updated series.
+    Latest,
+
+    /// Most popular series.
+    Popular,
+}
@@ -1,16 +1,34 @@
 //! The crate's main tyoes.
 
+mod base_urls;
+mod cancellation;
 mod chapter;
+mod check;
+mod event;
 mod filter;
+mod link;
+mod listing;
 mod options;
 mod page;
 mod pagination;
+mod proxy;
 mod series;
+mod summary;
 
+pub use cancellation::CancellationToken;
 pub use chapter::Chapter;
+pub use check::SelectorCheck;
+pub use event::Event;
+pub use event::EventHandler;
 pub use filter::Filter;
+pub use link::LinkKind;
+pub use listing::Listing;
 pub use options::Options;
 pub use page::Page;
+pub use proxy::Proxy;
 pub use series::Series;
+pub use summary::SeriesSummary;
 
+pub(crate) use base_urls::BaseUrls;
+pub(crate) use event::Events;
 pub(crate) use pagination::Pagination;
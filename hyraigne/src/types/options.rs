@@ -1,10 +1,30 @@
+use super::{
+    BaseUrls,
+    CancellationToken,
+    EventHandler,
+    Events,
+    Proxy,
+};
+#[cfg(feature = "async")]
+use crate::AsyncTransport;
+use crate::{
+    Cassette,
+    CookieJar,
+    HttpCache,
+    SelectorOverrides,
+    Transport,
+};
 use std::{
     cmp,
+    collections::BTreeMap,
     path::PathBuf,
+    sync::Arc,
     time,
 };
+use url::Url;
 
 /// Web spider options.
+#[derive(Clone)]
 pub struct Options {
     /// Delay between each request.
     pub(crate) delay: time::Duration,
@@ -14,6 +34,46 @@ pub struct Options {
 
     /// Output directory.
     pub(crate) output: PathBuf,
+
+    /// CSS selectors overriding those of the built-in web spiders.
+    pub(crate) selectors: SelectorOverrides,
+
+    /// HTTP transport, if not the default one.
+    pub(crate) transport: Option<Arc<dyn Transport>>,
+
+    /// HTTP transport of the async web spiders, if not the default one.
+    #[cfg(feature = "async")]
+    pub(crate) async_transport: Option<Arc<dyn AsyncTransport>>,
+
+    /// Cassette where HTTP interactions are recorded, if any.
+    pub(crate) cassette: Option<Arc<Cassette>>,
+
+    /// HTTP cache, if any.
+    pub(crate) cache: Option<Arc<HttpCache>>,
+
+    /// Base URLs remapped to other ones.
+    pub(crate) base_urls: BaseUrls,
+
+    /// Proxy used by the web spider, if any.
+    pub(crate) proxy: Option<Proxy>,
+
+    /// Proxies for specific websites, indexed by name.
+    pub(crate) site_proxies: BTreeMap<String, Proxy>,
+
+    /// Extra headers sent with every request.
+    pub(crate) headers: Vec<(String, String)>,
+
+    /// Extra headers for specific websites, indexed by name.
+    pub(crate) site_headers: BTreeMap<String, Vec<(String, String)>>,
+
+    /// Cookie jar shared by the web spiders, if any.
+    pub(crate) cookie_jar: Option<Arc<CookieJar>>,
+
+    /// Where the progress events are sent.
+    pub(crate) events: Events,
+
+    /// Token to stop the web spiders.
+    pub(crate) cancellation: CancellationToken,
 }
 
 impl Options {
@@ -32,6 +92,209 @@ impl Options {
             delay,
             retry,
             output,
+            selectors: SelectorOverrides::default(),
+            transport: None,
+            #[cfg(feature = "async")]
+            async_transport: None,
+            cassette: None,
+            cache: None,
+            base_urls: BaseUrls::default(),
+            proxy: None,
+            site_proxies: BTreeMap::new(),
+            headers: Vec::new(),
+            site_headers: BTreeMap::new(),
+            cookie_jar: None,
+            events: Events::default(),
+            cancellation: CancellationToken::default(),
+        }
+    }
+
+    /// Override the CSS selectors of the built-in web spiders.
+    #[must_use]
+    pub fn with_selectors(mut self, selectors: SelectorOverrides) -> Self {
+        self.selectors = selectors;
+        self
+    }
+
+    /// Send the HTTP requests through `transport` instead of the default one.
+    #[must_use]
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Send the HTTP requests of the async web spiders through `transport`.
+    ///
+    /// Without it, the async web spiders run the blocking transport (custom,
+    /// cassette, cache) on the threads dedicated to blocking tasks if any is
+    /// configured, and rely on `reqwest` otherwise. The cassette and the cache
+    /// are not used with an async transport.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn with_async_transport(
+        mut self,
+        transport: Arc<dyn AsyncTransport>,
+    ) -> Self {
+        self.async_transport = Some(transport);
+        self
+    }
+
+    /// Record every HTTP interaction into `cassette`.
+    ///
+    /// Use `Cassette::replay` as transport to replay them.
+    #[must_use]
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Cache the HTTP responses into `cache`.
+    #[must_use]
+    pub fn with_cache(mut self, cache: Arc<HttpCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Send every request through `proxy`.
+    ///
+    /// Only used by the default transport.
+    #[must_use]
+    pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Send the requests of the website called `site` through `proxy`.
+    ///
+    /// This takes precedence over the global proxy, and only applies to the
+    /// web spiders built by a `SiteRegistry`.
+    #[must_use]
+    pub fn with_site_proxy(mut self, site: &str, proxy: Proxy) -> Self {
+        self.site_proxies.insert(site.to_owned(), proxy);
+        self
+    }
+
+    /// Send the header `name` with every request.
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        set_header(&mut self.headers, name, value);
+        self
+    }
+
+    /// Send the header `name` with every request to the website `site`.
+    ///
+    /// This takes precedence over the global headers, and only applies to the
+    /// web spiders built by a `SiteRegistry`.
+    #[must_use]
+    pub fn with_site_header(
+        mut self,
+        site: &str,
+        name: &str,
+        value: &str,
+    ) -> Self {
+        let headers = self.site_headers.entry(site.to_owned()).or_default();
+        set_header(headers, name, value);
+        self
+    }
+
+    /// Identify as `user_agent` instead of the HTTP client default.
+    #[must_use]
+    pub fn with_user_agent(self, user_agent: &str) -> Self {
+        self.with_header("User-Agent", user_agent)
+    }
+
+    /// Share `cookie_jar` between the web spiders.
+    ///
+    /// Its cookies are sent along those of the web spiders, and it receives
+    /// the cookies set by the websites. Only used by the default transport.
+    #[must_use]
+    pub fn with_cookie_jar(mut self, cookie_jar: Arc<CookieJar>) -> Self {
+        self.cookie_jar = Some(cookie_jar);
+        self
+    }
+
+    /// Send the progress events (series found, page downloaded, …) to
+    /// `handler`.
+    ///
+    /// The handler is called synchronously, from the thread running the web
+    /// spider: forward the events to a channel for long processing.
+    #[must_use]
+    pub fn with_event_handler(mut self, handler: EventHandler) -> Self {
+        self.events = Events::new(handler);
+        self
+    }
+
+    /// Stop the web spiders when `token` is cancelled.
+    ///
+    /// The token is checked before each request, and interrupts the delays
+    /// between them: the operation in progress then fails with
+    /// `Error::Cancelled`.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Return the options to use for the website called `site`.
+    pub(crate) fn for_site(mut self, site: &str) -> Self {
+        if let Some(proxy) = self.site_proxies.get(site) {
+            self.proxy = Some(proxy.clone());
         }
+        if let Some(headers) = self.site_headers.get(site) {
+            for &(ref name, ref value) in headers {
+                set_header(&mut self.headers, name, value);
+            }
+        }
+        self
+    }
+
+    /// Send the requests targeting `base` (and below) to `replacement` instead.
+    ///
+    /// This allows to use a mirror, or to run the web spiders against a local
+    /// server, e.g. `https://api.mangadex.org/v2` to `http://127.0.0.1:8080/`.
+    ///
+    /// URLs on `replacement` are also recognized by `get_spider_for`.
+    #[must_use]
+    pub fn with_base_url(mut self, base: &Url, replacement: &Url) -> Self {
+        self.base_urls.insert(base, replacement);
+        self
+    }
+}
+
+/// Set the header `name` to `value`, replacing any previous value.
+fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: &str) {
+    headers.retain(|&(ref header, _)| !header.eq_ignore_ascii_case(name));
+    headers.push((name.to_owned(), value.to_owned()));
+}
+
+// Tests {{{
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_headers() {
+        let opts = Options::new(0, 0, std::env::temp_dir())
+            .with_user_agent("bibe")
+            .with_header("Accept-Language", "fr")
+            .with_site_header("webtoons", "user-agent", "Mozilla/5.0");
+
+        assert_eq!(
+            opts.clone().for_site("mangakakalot").headers,
+            vec![
+                ("User-Agent".to_owned(), "bibe".to_owned()),
+                ("Accept-Language".to_owned(), "fr".to_owned()),
+            ]
+        );
+        assert_eq!(
+            opts.for_site("webtoons").headers,
+            vec![
+                ("Accept-Language".to_owned(), "fr".to_owned()),
+                ("user-agent".to_owned(), "Mozilla/5.0".to_owned()),
+            ]
+        );
     }
 }
+
+// }}}
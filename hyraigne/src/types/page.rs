@@ -17,8 +17,9 @@ pub struct Page<'a> {
     /// URL of the page.
     pub(crate) main: Url,
 
-    /// Fallback URL (used if the first one doesn't work), if any.
-    pub(crate) fallback: Option<Url>,
+    /// URLs of the page on the alternate image servers, tried in turn if the
+    /// main one doesn't work.
+    pub(crate) fallbacks: Vec<Url>,
 }
 
 impl Page<'_> {
@@ -66,7 +67,7 @@ mod tests {
             id: 42,
             chapter: &chapter,
             main: Url::parse("http://example.com/10/uWu.jpg").unwrap(),
-            fallback: None,
+            fallbacks: Vec::new(),
         };
         let expected = "Downloads/Example/Example 10/030-042.jpg";
 
@@ -92,7 +93,7 @@ mod tests {
             id: 42,
             chapter: &chapter,
             main: Url::parse("http://example.com/10/uWu.jpg").unwrap(),
-            fallback: None,
+            fallbacks: Vec::new(),
         };
         let expected = "Downloads/Example/Example 030/042.jpg";
 
@@ -0,0 +1,24 @@
+use url::Url;
+
+/// A short description of a series, as listed in search results.
+pub struct SeriesSummary {
+    /// Series title.
+    pub(crate) title: String,
+
+    /// URL of the series page.
+    pub(crate) url: Url,
+}
+
+impl SeriesSummary {
+    /// Series title.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// URL of the series page, suitable for `Site::get_series`.
+    #[must_use]
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+}